@@ -0,0 +1,463 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameConfig, Level, Rng, Vector2d};
+
+/// Starts the [`Level::source`] of generated arenas, followed by `<kind>:<seed>:<width>x<height>`
+const SOURCE_PREFIX: &str = "arena:";
+
+/// Mixed into the seed of an arena, so its layout does not follow the fruits of a world created
+/// from the same seed
+const SEED_OFFSET: u32 = 0xA2E4A;
+
+/// A kind of procedurally generated level
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArenaKind {
+    /// Short wall pieces scattered over an open field
+    Pillars,
+    /// Rectangular rooms joined by narrow corridors
+    Rooms,
+    /// A maze without dead ends, its walls are knocked out to loops instead
+    Maze,
+}
+
+impl ArenaKind {
+    pub const ALL: [ArenaKind; 3] = [ArenaKind::Pillars, ArenaKind::Rooms, ArenaKind::Maze];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ArenaKind::Pillars => "pillars",
+            ArenaKind::Rooms => "rooms",
+            ArenaKind::Maze => "maze",
+        }
+    }
+
+    /// The kind with the given [`name`](Self::name)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// The arena of this kind for a field of the given size, the same layout for the same seed.
+    ///
+    /// Every free cell of the arena can be reached from every other one.
+    pub fn generate(self, size: Vector2d, seed: u32) -> Level {
+        let mut rng = Rng::new(seed ^ SEED_OFFSET);
+        let mut layout = Layout::new(size);
+        match self {
+            ArenaKind::Pillars => layout.pillars(&mut rng),
+            ArenaKind::Rooms => layout.rooms(&mut rng),
+            ArenaKind::Maze => layout.maze(&mut rng),
+        }
+        layout.connect();
+
+        let name = format!("{} {}", self.name(), seed);
+        let source = format!(
+            "{}{}:{}:{}x{}",
+            SOURCE_PREFIX,
+            self.name(),
+            seed,
+            size.x,
+            size.y
+        );
+        Level::from_walls(name, source, size, layout.walls)
+    }
+
+    /// The arena named by a source like `arena:maze:42:20x20`, the size may be left out for a
+    /// field of 20 by 20 cells. `None` if `source` does not name an arena at all.
+    pub(crate) fn parse_source(source: &str) -> Option<Result<Level, String>> {
+        let rest = source.strip_prefix(SOURCE_PREFIX)?;
+        let mut parts = rest.split(':');
+        let mut parse = || {
+            let kind = parts.next().unwrap_or_default();
+            let kind = Self::from_name(kind).ok_or_else(|| format!("unknown arena {}", kind))?;
+            let seed = parts
+                .next()
+                .and_then(|seed| seed.parse().ok())
+                .ok_or_else(|| format!("arena {} needs a seed", source))?;
+            let size = match parts.next() {
+                Some(size) => {
+                    let (width, height) = size
+                        .split_once('x')
+                        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                        .ok_or_else(|| format!("invalid arena size {}", size))?;
+                    Vector2d::new(width, height)
+                }
+                None => Vector2d::new(20, 20),
+            };
+            if parts.next().is_some() {
+                return Err(format!("invalid arena {}", source));
+            }
+            // checked before generating, long before the config is validated
+            let field = GameConfig::MIN_FIELD_SIZE as i32..=GameConfig::MAX_FIELD_SIZE as i32;
+            if !field.contains(&size.x) || !field.contains(&size.y) {
+                return Err(format!(
+                    "arena size {}x{} is not within {} and {}",
+                    size.x,
+                    size.y,
+                    GameConfig::MIN_FIELD_SIZE,
+                    GameConfig::MAX_FIELD_SIZE
+                ));
+            }
+            Ok(kind.generate(size, seed))
+        };
+        Some(parse())
+    }
+}
+
+/// The walls of an arena while it is generated
+struct Layout {
+    size: Vector2d,
+    walls: Vec<bool>,
+}
+
+impl Layout {
+    fn new(size: Vector2d) -> Self {
+        Self {
+            size,
+            walls: vec![false; (size.x * size.y) as usize],
+        }
+    }
+
+    fn index(&self, pos: Vector2d) -> usize {
+        (pos.y * self.size.x + pos.x) as usize
+    }
+
+    fn is_wall(&self, pos: Vector2d) -> bool {
+        self.walls[self.index(pos)]
+    }
+
+    fn set(&mut self, pos: Vector2d, wall: bool) {
+        let index = self.index(pos);
+        self.walls[index] = wall;
+    }
+
+    fn fill(&mut self, wall: bool) {
+        self.walls.iter_mut().for_each(|cell| *cell = wall);
+    }
+
+    /// The cells of the rectangle from `min` to `max`, both included, that lie on the field
+    fn rect(&self, min: Vector2d, max: Vector2d) -> impl Iterator<Item = Vector2d> {
+        let size = self.size;
+        (min.y.max(0)..=max.y.min(size.y - 1))
+            .flat_map(move |y| (min.x.max(0)..=max.x.min(size.x - 1)).map(move |x| (x, y)))
+            .map(|(x, y)| Vector2d::new(x, y))
+    }
+
+    /// The neighbours of a cell on the field
+    fn neighbours(&self, pos: Vector2d) -> impl Iterator<Item = Vector2d> {
+        let size = self.size;
+        [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .into_iter()
+            .map(move |(x, y)| Vector2d::new(pos.x + x, pos.y + y))
+            .filter(move |next| next.is_within(size))
+    }
+
+    /// Scatter pieces of one to three cells with a free cell around each, so they never touch
+    /// each other or the border
+    fn pillars(&mut self, rng: &mut Rng) {
+        let count = self.size.x * self.size.y / 30;
+        let mut placed = 0;
+        for _ in 0..count * 8 {
+            if placed == count {
+                break;
+            }
+
+            let length = 1 + rng.gen_below(3) as i32;
+            let (width, height) = match rng.gen_below(2) {
+                0 => (length, 1),
+                _ => (1, length),
+            };
+            if self.size.x < width + 2 || self.size.y < height + 2 {
+                continue;
+            }
+            let min = Vector2d::new(
+                1 + rng.gen_below((self.size.x - width - 1) as u32) as i32,
+                1 + rng.gen_below((self.size.y - height - 1) as u32) as i32,
+            );
+            let max = Vector2d::new(min.x + width - 1, min.y + height - 1);
+            let margin = Vector2d::new(1, 1);
+            let around = self.rect(
+                Vector2d::new(min.x - margin.x, min.y - margin.y),
+                Vector2d::new(max.x + margin.x, max.y + margin.y),
+            );
+            if around.into_iter().any(|pos| self.is_wall(pos)) {
+                continue;
+            }
+
+            for pos in self.rect(min, max).collect::<Vec<_>>() {
+                self.set(pos, true);
+            }
+            placed += 1;
+        }
+    }
+
+    /// Carve rooms that do not touch out of solid rock and join each to the next one with a
+    /// corridor bending once, plus a corridor from the last back to the first for a loop
+    fn rooms(&mut self, rng: &mut Rng) {
+        self.fill(true);
+        let max_width = (self.size.x / 3).max(3);
+        let max_height = (self.size.y / 3).max(3);
+        let mut rooms: Vec<(Vector2d, Vector2d)> = Vec::new();
+        for _ in 0..40 {
+            let width = (3 + rng.gen_below((max_width - 2) as u32) as i32).min(self.size.x);
+            let height = (3 + rng.gen_below((max_height - 2) as u32) as i32).min(self.size.y);
+            let min = Vector2d::new(
+                rng.gen_below((self.size.x - width + 1) as u32) as i32,
+                rng.gen_below((self.size.y - height + 1) as u32) as i32,
+            );
+            let max = Vector2d::new(min.x + width - 1, min.y + height - 1);
+            let overlaps = rooms.iter().any(|&(other_min, other_max)| {
+                min.x <= other_max.x + 1
+                    && other_min.x <= max.x + 1
+                    && min.y <= other_max.y + 1
+                    && other_min.y <= max.y + 1
+            });
+            if !overlaps {
+                rooms.push((min, max));
+            }
+        }
+
+        if rooms.is_empty() {
+            self.fill(false);
+            return;
+        }
+        for &(min, max) in &rooms {
+            for pos in self.rect(min, max).collect::<Vec<_>>() {
+                self.set(pos, false);
+            }
+        }
+
+        let center = |(min, max): (Vector2d, Vector2d)| {
+            Vector2d::new((min.x + max.x) / 2, (min.y + max.y) / 2)
+        };
+        let corridors = match rooms.len() {
+            1 | 2 => rooms.len() - 1,
+            count => count,
+        };
+        for i in 0..corridors {
+            let from = center(rooms[i]);
+            let to = center(rooms[(i + 1) % rooms.len()]);
+            let corner = match rng.gen_below(2) {
+                0 => Vector2d::new(to.x, from.y),
+                _ => Vector2d::new(from.x, to.y),
+            };
+            for (a, b) in [(from, corner), (corner, to)] {
+                let min = Vector2d::new(a.x.min(b.x), a.y.min(b.y));
+                let max = Vector2d::new(a.x.max(b.x), a.y.max(b.y));
+                for pos in self.rect(min, max).collect::<Vec<_>>() {
+                    self.set(pos, false);
+                }
+            }
+        }
+    }
+
+    /// Carve a perfect maze through the cells with even coordinates, then open up loops at its
+    /// dead ends
+    fn maze(&mut self, rng: &mut Rng) {
+        self.fill(true);
+        let start = Vector2d::new(0, 0);
+        self.set(start, false);
+        let mut stack = vec![start];
+        while let Some(&pos) = stack.last() {
+            let unvisited: Vec<_> = [(0, -2), (0, 2), (-2, 0), (2, 0)]
+                .into_iter()
+                .map(|(x, y)| Vector2d::new(pos.x + x, pos.y + y))
+                .filter(|&next| next.is_within(self.size) && self.is_wall(next))
+                .collect();
+            if unvisited.is_empty() {
+                stack.pop();
+                continue;
+            }
+
+            let next = unvisited[rng.gen_below(unvisited.len() as u32) as usize];
+            let between = Vector2d::new((pos.x + next.x) / 2, (pos.y + next.y) / 2);
+            self.set(between, false);
+            self.set(next, false);
+            stack.push(next);
+        }
+
+        // fields of even size leave a row or column beyond the last maze cells, open it up as a
+        // corridor along the edge
+        if self.size.x % 2 == 0 {
+            let x = self.size.x - 1;
+            for pos in self
+                .rect(Vector2d::new(x, 0), Vector2d::new(x, self.size.y - 1))
+                .collect::<Vec<_>>()
+            {
+                self.set(pos, false);
+            }
+        }
+        if self.size.y % 2 == 0 {
+            let y = self.size.y - 1;
+            for pos in self
+                .rect(Vector2d::new(0, y), Vector2d::new(self.size.x - 1, y))
+                .collect::<Vec<_>>()
+            {
+                self.set(pos, false);
+            }
+        }
+
+        // a dead end traps any snake longer than it, knock out one of its walls into another
+        // passage, which opens a loop
+        for pos in self
+            .rect(Vector2d::new(0, 0), self.size)
+            .collect::<Vec<_>>()
+        {
+            let passages = self.neighbours(pos).filter(|&next| !self.is_wall(next));
+            if self.is_wall(pos) || passages.count() > 1 {
+                continue;
+            }
+
+            let walls: Vec<_> = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+                .into_iter()
+                .filter(|&(x, y)| {
+                    let wall = Vector2d::new(pos.x + x, pos.y + y);
+                    let beyond = Vector2d::new(pos.x + 2 * x, pos.y + 2 * y);
+                    beyond.is_within(self.size) && self.is_wall(wall) && !self.is_wall(beyond)
+                })
+                .collect();
+            if !walls.is_empty() {
+                let (x, y) = walls[rng.gen_below(walls.len() as u32) as usize];
+                self.set(Vector2d::new(pos.x + x, pos.y + y), false);
+            }
+        }
+    }
+
+    /// Tunnel from the largest open area to every other one, until all free cells are connected
+    fn connect(&mut self) {
+        loop {
+            let areas = self.areas();
+            let count = areas.iter().flatten().max().map_or(0, |&max| max + 1);
+            if count < 2 {
+                return;
+            }
+
+            let mut sizes = vec![0; count];
+            for &area in areas.iter().flatten() {
+                sizes[area] += 1;
+            }
+            let main = (0..count).max_by_key(|&area| sizes[area]).unwrap();
+
+            // breadth first through walls and free cells alike, from the main area to the
+            // closest cell of another one
+            let mut came_from = vec![None; self.walls.len()];
+            let mut queue = VecDeque::new();
+            for pos in self.rect(Vector2d::new(0, 0), self.size) {
+                if areas[self.index(pos)] == Some(main) {
+                    came_from[self.index(pos)] = Some(pos);
+                    queue.push_back(pos);
+                }
+            }
+            let mut found = None;
+            while let Some(pos) = queue.pop_front() {
+                if matches!(areas[self.index(pos)], Some(area) if area != main) {
+                    found = Some(pos);
+                    break;
+                }
+                for next in self.neighbours(pos) {
+                    if came_from[self.index(next)].is_none() {
+                        came_from[self.index(next)] = Some(pos);
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            let mut pos = found.expect("the areas are on one field");
+            while areas[self.index(pos)] != Some(main) {
+                self.set(pos, false);
+                pos = came_from[self.index(pos)].unwrap();
+            }
+        }
+    }
+
+    /// The connected area each free cell belongs to, numbered from 0, `None` for walls
+    fn areas(&self) -> Vec<Option<usize>> {
+        let mut areas = vec![None; self.walls.len()];
+        let mut count = 0;
+        for start in self.rect(Vector2d::new(0, 0), self.size) {
+            if self.is_wall(start) || areas[self.index(start)].is_some() {
+                continue;
+            }
+
+            areas[self.index(start)] = Some(count);
+            let mut queue = VecDeque::from([start]);
+            while let Some(pos) = queue.pop_front() {
+                for next in self.neighbours(pos) {
+                    if !self.is_wall(next) && areas[self.index(next)].is_none() {
+                        areas[self.index(next)] = Some(count);
+                        queue.push_back(next);
+                    }
+                }
+            }
+            count += 1;
+        }
+        areas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Direction;
+
+    /// The number of free cells reachable from the first one
+    fn reachable(level: &Level) -> usize {
+        let size = level.size();
+        let free: Vec<_> = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| Vector2d::new(x, y)))
+            .filter(|&pos| !level.is_wall(pos))
+            .collect();
+        let mut seen = vec![free[0]];
+        let mut queue = VecDeque::from([free[0]]);
+        while let Some(pos) = queue.pop_front() {
+            for dir in Direction::ALL {
+                let next = pos + dir.to_vector();
+                if next.is_within(size) && !level.is_wall(next) && !seen.contains(&next) {
+                    seen.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        assert!(seen.len() > free.len() / 2);
+        free.len() - seen.len()
+    }
+
+    #[test]
+    fn arenas_keep_every_free_cell_reachable() {
+        for kind in ArenaKind::ALL {
+            for size in [(20, 20), (4, 4), (13, 7), (31, 24)] {
+                for seed in 0..20 {
+                    let level = kind.generate(Vector2d::new(size.0, size.1), seed);
+                    assert_eq!(reachable(&level), 0, "{}", level.source());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn seeds_share_arenas() {
+        let size = Vector2d::new(20, 20);
+        for kind in ArenaKind::ALL {
+            let level = kind.generate(size, 42);
+            assert_eq!(level, kind.generate(size, 42));
+            let walls: Vec<_> = level.walls().collect();
+            assert_ne!(walls, kind.generate(size, 43).walls().collect::<Vec<_>>());
+            assert_eq!(Level::find(level.source()), Ok(level));
+        }
+        let level = Level::find("arena:maze:42").unwrap();
+        assert_eq!(level, ArenaKind::Maze.generate(size, 42));
+        assert!(Level::find("arena:lake:42").is_err());
+        assert!(Level::find("arena:maze:42:20").is_err());
+    }
+
+    #[test]
+    fn arena_sizes_stay_within_the_field_limits() {
+        assert!(Level::find("arena:maze:1:256x4").is_ok());
+        assert!(Level::find("arena:maze:1:3x20").is_err());
+        assert!(Level::find("arena:maze:1:257x20").is_err());
+        assert!(Level::find("arena:maze:1:50000x50000").is_err());
+        assert!(Level::find("arena:maze:1:-20x-20").is_err());
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{
+    controller::step_towards, Direction, FruitEffect, PathfindingController, Snake,
+    SnakeController, Vector2d, WallMode, World,
+};
+
+/// A closed path through every cell of the field, each cell visited exactly once.
+///
+/// A snake that only ever follows it can not die and fills the whole field eventually.
+#[derive(Clone, Debug)]
+pub struct HamiltonianCycle {
+    size: Vector2d,
+    /// The cells in the order they are visited
+    cells: Vec<Vector2d>,
+    /// The position of each cell in `cells`, indexed by `y * width + x`
+    positions: Vec<usize>,
+}
+
+impl HamiltonianCycle {
+    /// A cycle over a field of the given size, if there is one.
+    ///
+    /// With solid walls a cycle needs an even number of cells, which rules out fields whose width
+    /// and height are both odd. Wrapping walls connect the edges, so every field has a cycle.
+    pub fn new(size: Vector2d, walls: WallMode) -> Option<Self> {
+        if size.x < 2 || size.y < 2 {
+            return None;
+        }
+
+        let cells = if size.y % 2 == 0 {
+            rows_cycle(size)
+        } else if size.x % 2 == 0 {
+            let flipped = rows_cycle(Vector2d::new(size.y, size.x));
+            flipped
+                .into_iter()
+                .map(|pos| Vector2d::new(pos.y, pos.x))
+                .collect()
+        } else if walls == WallMode::Wrap {
+            // cover all rows but the last, then detour through the last one between the first two
+            // cells of the top row: up across the edge, leftwards around the whole row and down
+            // across the edge again
+            let mut cells = rows_cycle(Vector2d::new(size.x, size.y - 1));
+            let detour = (0..size.x).map(|i| Vector2d::new((size.x - i) % size.x, size.y - 1));
+            cells.splice(1..1, detour);
+            cells
+        } else {
+            return None;
+        };
+
+        let mut positions = vec![0; (size.x * size.y) as usize];
+        for (i, pos) in cells.iter().enumerate() {
+            positions[(pos.y * size.x + pos.x) as usize] = i;
+        }
+
+        Some(Self {
+            size,
+            cells,
+            positions,
+        })
+    }
+
+    /// The number of cells in the cycle, which is the number of cells of the field
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether the cycle is empty, which never happens for a valid field
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Where a cell of the field lies on the cycle
+    pub fn position(&self, pos: Vector2d) -> usize {
+        self.positions[(pos.y * self.size.x + pos.x) as usize]
+    }
+
+    /// The cell visited after `pos`
+    pub fn next(&self, pos: Vector2d) -> Vector2d {
+        self.cells[(self.position(pos) + 1) % self.len()]
+    }
+
+    /// The number of steps along the cycle from `from` to `to`
+    pub fn distance(&self, from: Vector2d, to: Vector2d) -> usize {
+        (self.position(to) + self.len() - self.position(from)) % self.len()
+    }
+
+    /// Whether the segments of a snake follow the cycle from its tail to its head, possibly
+    /// skipping cells, but without going around it more than once
+    pub fn is_aligned(&self, snake: &Snake) -> bool {
+        let cells = snake.cells();
+        let steps: usize = cells
+            .iter()
+            .zip(cells.iter().skip(1))
+            .map(|(&later, &earlier)| self.distance(earlier, later))
+            .sum();
+        steps == self.distance(snake.tail(), snake.head())
+    }
+}
+
+/// A cycle that runs along the top row, snakes back and forth through the other rows while
+/// leaving the first column free and returns up that column. `size.y` has to be even.
+fn rows_cycle(size: Vector2d) -> Vec<Vector2d> {
+    let mut cells: Vec<_> = (0..size.x).map(|x| Vector2d::new(x, 0)).collect();
+    for y in 1..size.y {
+        if y % 2 == 1 {
+            cells.extend((1..size.x).rev().map(|x| Vector2d::new(x, y)));
+        } else {
+            cells.extend((1..size.x).map(|x| Vector2d::new(x, y)));
+        }
+    }
+    cells.extend((1..size.y).rev().map(|y| Vector2d::new(0, y)));
+    cells
+}
+
+/// Plays a perfect game of classic snake by following a [`HamiltonianCycle`], cutting it short
+/// towards the fruit where that can not trap the snake.
+///
+/// The snake keeps its segments in cycle order from tail to head, so any free cell between its
+/// head and its tail along the cycle can be entered safely, as long as the cells it still grows by
+/// fit in before the tail. On fields without a cycle or with the
+/// walls of a level inside them, and for a snake taken over in a shape that does not follow the
+/// cycle, it plays like the [`PathfindingController`] instead.
+///
+/// Poisonous fruits on the cycle are stepped around where the field allows it or reached only
+/// after they disappeared, but one placed in a stretch the snake can not leave still ends the game.
+#[derive(Clone, Debug)]
+pub struct Autopilot {
+    cycle: Option<HamiltonianCycle>,
+    fallback: PathfindingController,
+}
+
+impl Autopilot {
+    pub fn new(world: &World) -> Self {
+        let config = world.config();
+        // the cycle runs through every cell, walls inside the field break it
+        let has_walls = matches!(&config.level, Some(level) if level.walls().next().is_some());
+        Self {
+            cycle: HamiltonianCycle::new(config.field_size(), config.walls).filter(|_| !has_walls),
+            fallback: PathfindingController,
+        }
+    }
+
+    /// The cycle followed, `None` if the field has none and the fallback plays instead
+    pub fn cycle(&self) -> Option<&HamiltonianCycle> {
+        self.cycle.as_ref()
+    }
+}
+
+impl SnakeController for Autopilot {
+    fn next_direction(&mut self, world: &World, snake: usize) -> Option<Direction> {
+        let me = world.snake(snake);
+        let cycle = match &self.cycle {
+            Some(cycle) if cycle.is_aligned(me) => cycle,
+            _ => return self.fallback.next_direction(world, snake),
+        };
+
+        let head = me.head();
+        let tail = me.tail();
+        // the free stretch of the cycle ahead of the head, the whole field for a lone head
+        let room = match cycle.distance(head, tail) {
+            0 => cycle.len(),
+            room => room,
+        };
+        // without a fruit the snake just follows the cycle
+        let to_fruit = world.fruit().map_or(1, |fruit| cycle.distance(head, fruit));
+        // the poisonous fruits on the stretch, by their distance and the tick they disappear at
+        let poison: Vec<_> = world
+            .fruits()
+            .iter()
+            .filter(|fruit| world.kind(fruit).effect == FruitEffect::Poison)
+            .map(|fruit| (cycle.distance(head, fruit.pos), fruit.expires))
+            .filter(|&(distance, _)| distance < room)
+            .collect();
+        // whether a step that far along the cycle skips each of them or gets there after it is gone
+        let avoids_poison = |step: usize| {
+            poison.iter().all(|&(distance, expires)| {
+                let arrival = (world.ticks() + 1 + distance as u64).checked_sub(step as u64);
+                step > distance || matches!((expires, arrival), (Some(e), Some(a)) if e <= a)
+            })
+        };
+
+        let steps: Vec<_> = Direction::ALL
+            .into_iter()
+            .filter(|&dir| Some(dir.opposite()) != me.direction())
+            .map(|dir| world.wrap(head + dir.to_vector()))
+            .filter(|&pos| pos.is_within(world.config().field_size()))
+            .map(|pos| (cycle.distance(head, pos), pos))
+            // the tail moves on unless the snake grows
+            .filter(|&(distance, pos)| {
+                let poisoned = world
+                    .fruit_at(pos)
+                    .is_some_and(|fruit| world.kind(fruit).effect == FruitEffect::Poison);
+                !world.is_blocked(pos)
+                    || (pos == tail && !me.is_growing())
+                    || (poisoned && distance < room && avoids_poison(distance))
+            })
+            .collect();
+
+        // never skip the fruit, cut in so close behind the tail that it stays in place longer than
+        // the stretch left, or leave holes in a snake taking half the field, which the tail has to
+        // pass before the stretch grows again
+        let shortcut = steps
+            .iter()
+            .filter(|&&(distance, pos)| {
+                let growth = me.growth() as usize + growth_at(world, pos) as usize;
+                distance <= to_fruit
+                    && distance + growth < room
+                    && me.length() + growth < cycle.len() / 2
+                    && avoids_poison(distance)
+            })
+            .max_by_key(|&&(distance, _)| distance);
+        // otherwise the closest cell of the stretch, stepping around poison where it can, and a
+        // free cell behind the head as a last resort
+        let step = shortcut.or_else(|| {
+            steps.iter().min_by_key(|&&(distance, pos)| {
+                let ahead = distance < room || pos == tail;
+                (!(ahead && avoids_poison(distance)), !ahead, distance)
+            })
+        });
+        match step {
+            Some(&(_, pos)) => step_towards(world, snake, pos),
+            None => self.fallback.next_direction(world, snake),
+        }
+    }
+}
+
+/// The number of cells a snake grows by when its head moves to `pos`
+fn growth_at(world: &World, pos: Vector2d) -> u32 {
+    match world.fruit_at(pos).map(|fruit| world.kind(fruit).effect) {
+        Some(FruitEffect::Grow(cells)) => cells,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{FruitKind, GameConfig, Rng, StepOutcome};
+
+    fn assert_cycle(width: i32, height: i32, walls: WallMode) {
+        let size = Vector2d::new(width, height);
+        let cycle = HamiltonianCycle::new(size, walls).expect("no cycle");
+        assert_eq!(cycle.len(), (width * height) as usize);
+
+        let visited: HashSet<_> = cycle.cells.iter().copied().collect();
+        assert_eq!(visited.len(), cycle.len());
+        for (i, &pos) in cycle.cells.iter().enumerate() {
+            assert!(pos.is_within(size));
+            assert_eq!(cycle.position(pos), i);
+
+            let next = cycle.next(pos);
+            let step = Direction::ALL.into_iter().find(|dir| {
+                let moved = pos + dir.to_vector();
+                match walls {
+                    WallMode::Solid => moved == next,
+                    WallMode::Wrap => moved.rem_euclid(size) == next,
+                }
+            });
+            assert!(step.is_some(), "{:?} -> {:?} is no step", pos, next);
+        }
+    }
+
+    #[test]
+    fn cycles_cover_even_fields() {
+        assert_cycle(4, 4, WallMode::Solid);
+        assert_cycle(20, 20, WallMode::Solid);
+        assert_cycle(7, 4, WallMode::Solid);
+        assert_cycle(4, 7, WallMode::Solid);
+        assert_cycle(9, 6, WallMode::Wrap);
+    }
+
+    #[test]
+    fn odd_fields_need_wrapping_walls() {
+        assert!(HamiltonianCycle::new(Vector2d::new(5, 5), WallMode::Solid).is_none());
+        assert_cycle(5, 5, WallMode::Wrap);
+        assert_cycle(7, 9, WallMode::Wrap);
+    }
+
+    /// Let the autopilot play a game on a field of the given size until it is over
+    fn play(width: u32, height: u32, walls: WallMode, fruits: Vec<FruitKind>) -> StepOutcome {
+        let config = GameConfig {
+            field_width: width,
+            field_height: height,
+            walls,
+            fruits,
+            ..GameConfig::default()
+        };
+        let mut world = World::new(&config, Rng::new(1));
+        let mut autopilot = Autopilot::new(&world);
+        let mut outcome = StepOutcome::Idle;
+        for _ in 0..10_000 {
+            if let Some(dir) = autopilot.next_direction(&world, 0) {
+                world.input(0, dir);
+            }
+            outcome = world.update();
+            if outcome.is_over() {
+                break;
+            }
+        }
+        outcome
+    }
+
+    #[test]
+    fn autopilot_fills_the_field() {
+        for (width, height, walls) in [(6, 4, WallMode::Solid), (5, 5, WallMode::Wrap)] {
+            let outcome = play(width, height, walls, FruitKind::classic_table());
+            assert_eq!(outcome, StepOutcome::Won);
+        }
+    }
+
+    #[test]
+    fn autopilot_fills_the_field_with_every_kind_of_fruit() {
+        // melons make the snake grow by several cells, poison has to be stepped around
+        for (width, height, walls) in [(6, 4, WallMode::Solid), (5, 5, WallMode::Wrap)] {
+            let outcome = play(width, height, walls, FruitKind::default_table());
+            assert_eq!(outcome, StepOutcome::Won);
+        }
+    }
+}
@@ -0,0 +1,341 @@
+//! Runs batches of games without a window, steered by one of the built-in controllers.
+
+use std::{env, path::PathBuf, process};
+
+use serde::Serialize;
+use snake_pixels::{
+    ArenaKind, Autopilot, DeathCause, Direction, FruitKind, GameConfig, HamiltonianCycle, Level,
+    Opponents, Rng, Screenshots, ScriptedController, SnakeController, Strategy, Theme, WallMode,
+    World,
+};
+
+const USAGE: &str = "\
+Usage: snake-sim [OPTIONS]
+
+Options:
+    --games <N>          Number of games to run [default: 100]
+    --seed <SEED>        Seed of the first game, the following games count up from it [default: 1]
+    --controller <NAME>  random, greedy, pathfinding, autopilot or script:<UDLR...>
+                         [default: greedy]
+    --opponents <N>      Number of computer controlled opponents, 0 to 3 [default: 0]
+    --opponent-strategy <NAME>
+                         random, greedy or pathfinding [default: greedy]
+    --width <CELLS>      Width of the field [default: 20]
+    --height <CELLS>     Height of the field [default: 20]
+    --walls <MODE>       solid or wrap [default: solid]
+    --level <LEVEL>      pillars, cross, rooms, tunnel or the path of a level file
+    --arena <KIND>       Play random arenas generated from each seed: pillars, rooms or maze
+    --fruits <TABLE>     default or classic, which only has apples [default: default]
+    --no-power-ups       Never place power-ups on the field
+    --max-ticks <N>      Stop a game that survives this long [default: 10000]
+    --format <FORMAT>    csv or json [default: csv]
+    --screenshots <DIR>  Save the last frame of every game here as seed-<SEED>.png
+    --screenshot-scale <N>
+                         Enlarge screenshots by this factor, up to 16 [default: 1]
+    -h, --help           Print this help";
+
+#[derive(Clone, Debug)]
+enum ControllerKind {
+    Builtin(Strategy),
+    Autopilot,
+    Scripted(Vec<Direction>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Debug)]
+struct Options {
+    games: u32,
+    seed: u32,
+    controller: ControllerKind,
+    config: GameConfig,
+    max_ticks: u64,
+    format: Format,
+    screenshots: Option<PathBuf>,
+    screenshot_scale: u32,
+}
+
+/// The result of a single simulated game
+#[derive(Clone, Debug, Serialize)]
+struct GameStats {
+    seed: u32,
+    score: u32,
+    length: usize,
+    ticks: u64,
+    /// `wall`, `body`, `snake`, `poison`, `won` if the snake filled the field or `timeout` if it
+    /// survived `--max-ticks`
+    death: &'static str,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+struct Summary {
+    games: u32,
+    mean_length: f64,
+    max_length: usize,
+    mean_score: f64,
+    mean_ticks: f64,
+    wall_deaths: u32,
+    body_deaths: u32,
+    snake_deaths: u32,
+    poison_deaths: u32,
+    wins: u32,
+    timeouts: u32,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    summary: &'a Summary,
+    games: &'a [GameStats],
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|e| {
+        eprintln!("{}\n\n{}", e, USAGE);
+        process::exit(2);
+    });
+
+    let size = options.config.field_size();
+    if let ControllerKind::Autopilot = options.controller {
+        if HamiltonianCycle::new(size, options.config.walls).is_none() {
+            eprintln!(
+                "No Hamiltonian cycle on a {}x{} field with solid walls, \
+                 the autopilot falls back to pathfinding",
+                size.x, size.y
+            );
+        }
+    }
+
+    let games: Vec<_> = (0..options.games)
+        .map(|i| simulate(&options, options.seed.wrapping_add(i)))
+        .collect();
+    let summary = summarize(&games);
+
+    match options.format {
+        Format::Csv => {
+            println!("seed,score,length,ticks,death");
+            for game in &games {
+                println!(
+                    "{},{},{},{},{}",
+                    game.seed, game.score, game.length, game.ticks, game.death
+                );
+            }
+            eprintln!(
+                "games: {}, mean length: {:.2}, max length: {}, mean score: {:.2}, \
+                 mean ticks: {:.2}, deaths: {} wall, {} body, {} snake, {} poison, \
+                 {} wins, {} timeouts",
+                summary.games,
+                summary.mean_length,
+                summary.max_length,
+                summary.mean_score,
+                summary.mean_ticks,
+                summary.wall_deaths,
+                summary.body_deaths,
+                summary.snake_deaths,
+                summary.poison_deaths,
+                summary.wins,
+                summary.timeouts
+            );
+        }
+        Format::Json => {
+            let report = Report {
+                summary: &summary,
+                games: &games,
+            };
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+    }
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        games: 100,
+        seed: 1,
+        controller: ControllerKind::Builtin(Strategy::Greedy),
+        config: GameConfig::default(),
+        max_ticks: 10_000,
+        format: Format::Csv,
+        screenshots: None,
+        screenshot_scale: 1,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--no-power-ups" => {
+                options.config.power_ups = false;
+                continue;
+            }
+            _ => (),
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", arg))?;
+        let invalid = |_| format!("Invalid value for {}: {}", arg, value);
+        match arg.as_str() {
+            "--games" => options.games = value.parse().map_err(invalid)?,
+            "--seed" => options.seed = value.parse().map_err(invalid)?,
+            "--max-ticks" => options.max_ticks = value.parse().map_err(invalid)?,
+            "--controller" => options.controller = parse_controller(&value)?,
+            "--width" => options.config.field_width = value.parse().map_err(invalid)?,
+            "--height" => options.config.field_height = value.parse().map_err(invalid)?,
+            "--opponents" => options.config.opponents = value.parse().map_err(invalid)?,
+            "--opponent-strategy" => {
+                options.config.opponent_strategy = Strategy::from_name(&value)
+                    .ok_or_else(|| format!("Unknown strategy {}", value))?
+            }
+            "--level" => options.config.level = Some(Level::find(&value)?),
+            "--arena" => {
+                options.config.arena = Some(
+                    ArenaKind::from_name(&value)
+                        .ok_or_else(|| format!("Unknown arena {}", value))?,
+                )
+            }
+            "--fruits" => {
+                options.config.fruits = match value.as_str() {
+                    "classic" => FruitKind::classic_table(),
+                    "default" => FruitKind::default_table(),
+                    _ => return Err(format!("Unknown fruit table {}", value)),
+                }
+            }
+            "--walls" => {
+                options.config.walls = WallMode::from_name(&value)
+                    .ok_or_else(|| format!("Unknown wall mode {}", value))?
+            }
+            "--format" => {
+                options.format = match value.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => return Err(format!("Unknown format {}", value)),
+                }
+            }
+            "--screenshots" => options.screenshots = Some(value.into()),
+            "--screenshot-scale" => {
+                let scale = value.parse().map_err(invalid)?;
+                if !(1..=Screenshots::MAX_SCALE).contains(&scale) {
+                    return Err(format!(
+                        "{} is not within 1 and {}",
+                        arg,
+                        Screenshots::MAX_SCALE
+                    ));
+                }
+                options.screenshot_scale = scale;
+            }
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    options.config.validate()?;
+    Ok(options)
+}
+
+fn parse_controller(name: &str) -> Result<ControllerKind, String> {
+    match Strategy::from_name(name) {
+        Some(strategy) => Ok(ControllerKind::Builtin(strategy)),
+        None if name == "autopilot" => Ok(ControllerKind::Autopilot),
+        None => {
+            let script = name
+                .strip_prefix("script:")
+                .ok_or_else(|| format!("Unknown controller {}", name))?;
+            script
+                .chars()
+                .map(|c| {
+                    Direction::from_char(c)
+                        .ok_or_else(|| format!("Invalid direction {:?} in script", c))
+                })
+                .collect::<Result<_, _>>()
+                .map(ControllerKind::Scripted)
+        }
+    }
+}
+
+/// Play one game until the snake dies or `max_ticks` is reached
+fn simulate(options: &Options, seed: u32) -> GameStats {
+    let config = options.config.for_seed(seed);
+    let mut world = World::new(&config, Rng::new(seed));
+    let mut controller: Box<dyn SnakeController> = match &options.controller {
+        // derive the controller's randomness from the seed as well, to keep runs reproducible
+        ControllerKind::Builtin(strategy) => strategy.controller(Rng::new(seed ^ 0x5EED)),
+        ControllerKind::Autopilot => Box::new(Autopilot::new(&world)),
+        ControllerKind::Scripted(script) => Box::new(ScriptedController::new(script.clone())),
+    };
+    let mut opponents = Opponents::new(&config, seed);
+    let mut ticks = 0;
+    while ticks < options.max_ticks {
+        if let Some(dir) = controller.next_direction(&world, 0) {
+            world.input(0, dir);
+        }
+        opponents.steer(&mut world);
+
+        ticks += 1;
+        if world.update().is_over() {
+            break;
+        }
+    }
+
+    if let Some(dir) = &options.screenshots {
+        let screenshots = Screenshots::new(dir, options.screenshot_scale);
+        let frame = world.render(1.0, &Theme::default(), None);
+        if let Err(e) = screenshots.save_as(&frame, format!("seed-{}.png", seed)) {
+            eprintln!("Could not save screenshot of seed {}: {}", seed, e);
+        }
+    }
+
+    let snake = world.snake(0);
+    GameStats {
+        seed,
+        score: snake.score(),
+        length: snake.length(),
+        ticks,
+        death: match snake.death_cause() {
+            Some(DeathCause::Wall) => "wall",
+            Some(DeathCause::Body) => "body",
+            Some(DeathCause::Snake | DeathCause::HeadOn) => "snake",
+            Some(DeathCause::Poison) => "poison",
+            None if world.has_won() => "won",
+            None => "timeout",
+        },
+    }
+}
+
+fn summarize(games: &[GameStats]) -> Summary {
+    let mut summary = Summary {
+        games: games.len() as u32,
+        ..Summary::default()
+    };
+    if games.is_empty() {
+        return summary;
+    }
+
+    let mut total_length = 0;
+    let mut total_score = 0;
+    let mut total_ticks = 0;
+    for game in games {
+        total_length += game.length;
+        total_score += game.score as u64;
+        total_ticks += game.ticks;
+        summary.max_length = summary.max_length.max(game.length);
+        match game.death {
+            "wall" => summary.wall_deaths += 1,
+            "body" => summary.body_deaths += 1,
+            "snake" => summary.snake_deaths += 1,
+            "poison" => summary.poison_deaths += 1,
+            "won" => summary.wins += 1,
+            _ => summary.timeouts += 1,
+        }
+    }
+
+    let count = games.len() as f64;
+    summary.mean_length = total_length as f64 / count;
+    summary.mean_score = total_score as f64 / count;
+    summary.mean_ticks = total_ticks as f64 / count;
+    summary
+}
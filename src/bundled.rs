@@ -0,0 +1,35 @@
+use std::{fmt, io};
+
+/// Data compiled into the game, by the name it is referred to with
+pub(crate) type Bundled<T> = [(&'static str, T)];
+
+/// The names of the bundled data, in the order they are listed
+pub(crate) fn names<T>(bundled: &'static Bundled<T>) -> impl Iterator<Item = &'static str> {
+    bundled.iter().map(|&(name, _)| name)
+}
+
+/// Parse the bundled data with the given name.
+///
+/// Bundled data is checked by the tests of the module using it, so it can not fail to parse.
+pub(crate) fn get<T, U, E: fmt::Debug>(
+    bundled: &'static Bundled<T>,
+    name: &str,
+    parse: impl FnOnce(&'static str, &'static T) -> Result<U, E>,
+) -> Option<U> {
+    let (name, data) = bundled.iter().find(|&(bundled, _)| *bundled == name)?;
+    Some(parse(name, data).unwrap())
+}
+
+/// The bundled data with the given name, otherwise the file at that path, `kind` names what is
+/// looked for in errors
+pub(crate) fn find<'a, U>(
+    kind: &str,
+    name: &'a str,
+    bundled: impl FnOnce(&'a str) -> Option<U>,
+    load: impl FnOnce(&'a str) -> io::Result<U>,
+) -> Result<U, String> {
+    match bundled(name) {
+        Some(found) => Ok(found),
+        None => load(name).map_err(|e| format!("{} {}: {}", kind, name, e)),
+    }
+}
@@ -0,0 +1,180 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ArenaKind, Difficulty, FruitKind, Level, SpeedCurve, Strategy, Vector2d, WallMode, HUD_HEIGHT,
+};
+
+/// The size of the board and how it is drawn, loaded from a config file or the command line
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    /// The number of cells in a row of the board, unless a level sets it
+    pub field_width: u32,
+    /// The number of cells in a column of the board, unless a level sets it
+    pub field_height: u32,
+    /// The walls inside the field and its size, given by the name of a bundled level or the path
+    /// of a level file
+    pub level: Option<Level>,
+    /// Generate the walls of each game from its seed instead of loading a level
+    pub arena: Option<ArenaKind>,
+    /// The width and height of a single cell in pixels
+    pub cell_size: u32,
+    pub walls: WallMode,
+    /// 1 for a single player game, 2 for versus on a shared keyboard
+    pub players: u32,
+    /// The number of computer controlled snakes sharing the field
+    pub opponents: u32,
+    pub opponent_strategy: Strategy,
+    pub difficulty: Difficulty,
+    /// A custom speed curve replacing the one of the difficulty preset
+    pub speed: Option<SpeedCurve>,
+    /// The kinds of fruit that appear, the regular one first
+    pub fruits: Vec<FruitKind>,
+    /// Whether power-ups appear next to the regular fruit now and then
+    pub power_ups: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            field_width: 20,
+            field_height: 20,
+            level: None,
+            arena: None,
+            cell_size: 40,
+            walls: WallMode::Solid,
+            players: 1,
+            opponents: 0,
+            opponent_strategy: Strategy::Greedy,
+            difficulty: Difficulty::Normal,
+            speed: None,
+            fruits: FruitKind::default_table(),
+            power_ups: true,
+        }
+    }
+}
+
+impl GameConfig {
+    pub(crate) const MIN_FIELD_SIZE: u32 = 4;
+    pub(crate) const MAX_FIELD_SIZE: u32 = 256;
+    const MIN_CELL_SIZE: u32 = 4;
+    const MAX_CELL_SIZE: u32 = 128;
+    const MAX_PLAYERS: u32 = 2;
+    pub const MAX_OPPONENTS: u32 = 3;
+
+    /// Load a config file, missing keys keep their default
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let config: Self =
+            toml::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        config
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(config)
+    }
+
+    /// Check that the board and cells are neither degenerate nor absurdly large
+    pub fn validate(&self) -> Result<(), String> {
+        let field = Self::MIN_FIELD_SIZE as i32..=Self::MAX_FIELD_SIZE as i32;
+        let size = self.field_size();
+        if !field.contains(&size.x) || !field.contains(&size.y) {
+            return Err(format!(
+                "field size {}x{} is not within {} and {}",
+                size.x,
+                size.y,
+                Self::MIN_FIELD_SIZE,
+                Self::MAX_FIELD_SIZE
+            ));
+        }
+
+        let cell = Self::MIN_CELL_SIZE..=Self::MAX_CELL_SIZE;
+        if !cell.contains(&self.cell_size) {
+            return Err(format!(
+                "cell size {} is not within {} and {}",
+                self.cell_size,
+                Self::MIN_CELL_SIZE,
+                Self::MAX_CELL_SIZE
+            ));
+        }
+
+        if !(1..=Self::MAX_PLAYERS).contains(&self.players) {
+            return Err(format!(
+                "{} players is not within 1 and {}",
+                self.players,
+                Self::MAX_PLAYERS
+            ));
+        }
+
+        if self.opponents > Self::MAX_OPPONENTS {
+            return Err(format!(
+                "{} opponents is more than {}",
+                self.opponents,
+                Self::MAX_OPPONENTS
+            ));
+        }
+
+        if self.level.is_some() && self.arena.is_some() {
+            return Err("a level and a random arena can not be played at once".to_owned());
+        }
+
+        if let Some(level) = &self.level {
+            let snakes = (self.players + self.opponents) as usize;
+            if level.free_cells() < snakes + 1 {
+                return Err(format!(
+                    "level {} has {} free cells, too few for {} snakes and a fruit",
+                    level.name(),
+                    level.free_cells(),
+                    snakes
+                ));
+            }
+        }
+
+        if let Some(speed) = &self.speed {
+            speed.validate()?;
+        }
+
+        FruitKind::validate_table(&self.fruits)?;
+
+        Ok(())
+    }
+
+    /// The speed curve games are played with
+    pub fn speed_curve(&self) -> SpeedCurve {
+        self.speed.unwrap_or_else(|| self.difficulty.speed_curve())
+    }
+
+    /// The config a world created from the given seed is played with, with the random arena of
+    /// that seed as its level
+    pub fn for_seed(&self, seed: u32) -> GameConfig {
+        let mut config = self.clone();
+        if let Some(kind) = config.arena.take() {
+            config.level = Some(kind.generate(self.field_size(), seed));
+        }
+        config
+    }
+
+    /// The number of cells in each direction, those of the level if there is one
+    pub fn field_size(&self) -> Vector2d {
+        match &self.level {
+            Some(level) => level.size(),
+            None => Vector2d::new(self.field_width as i32, self.field_height as i32),
+        }
+    }
+
+    /// The width of a whole frame in pixels
+    pub fn frame_width(&self) -> u32 {
+        self.field_size().x as u32 * self.cell_size
+    }
+
+    /// The height of a whole frame in pixels, HUD strip and playfield
+    pub fn frame_height(&self) -> u32 {
+        HUD_HEIGHT + self.playfield_height()
+    }
+
+    /// The height of the playfield below the HUD strip in pixels
+    pub fn playfield_height(&self) -> u32 {
+        self.field_size().y as u32 * self.cell_size
+    }
+}
@@ -0,0 +1,333 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Direction, GameConfig, Rng, Vector2d, World};
+
+/// Steers a snake, used by bots and the headless simulator
+pub trait SnakeController {
+    /// Choose the turn of the snake with the given index for the next tick, `None` keeps its
+    /// current direction
+    fn next_direction(&mut self, world: &World, snake: usize) -> Option<Direction>;
+}
+
+/// One of the built-in controllers, from weakest to strongest
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    Random,
+    #[default]
+    Greedy,
+    Pathfinding,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Strategy::Random, Strategy::Greedy, Strategy::Pathfinding];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Random => "random",
+            Strategy::Greedy => "greedy",
+            Strategy::Pathfinding => "pathfinding",
+        }
+    }
+
+    /// The strategy with the given [`name`](Self::name)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == name)
+    }
+
+    /// The next stronger strategy, wrapping back to the weakest one
+    pub fn next(self) -> Self {
+        match self {
+            Strategy::Random => Strategy::Greedy,
+            Strategy::Greedy => Strategy::Pathfinding,
+            Strategy::Pathfinding => Strategy::Random,
+        }
+    }
+
+    /// A controller playing this strategy, `rng` is only used by the random one
+    pub fn controller(self, rng: Rng) -> Box<dyn SnakeController> {
+        match self {
+            Strategy::Random => Box::new(RandomController::new(rng)),
+            Strategy::Greedy => Box::new(GreedyController),
+            Strategy::Pathfinding => Box::new(PathfindingController),
+        }
+    }
+}
+
+/// The computer controlled snakes of a world, whose snakes come after those of the players
+pub struct Opponents {
+    controllers: Vec<Box<dyn SnakeController>>,
+}
+
+impl Opponents {
+    /// One controller of the configured strategy per opponent, seeded from the seed of the world
+    /// so games with opponents can be replayed
+    pub fn new(config: &GameConfig, seed: u32) -> Self {
+        let controllers = (0..config.opponents)
+            .map(|i| {
+                config
+                    .opponent_strategy
+                    .controller(Rng::new(seed ^ 0xB075 ^ i))
+            })
+            .collect();
+        Self { controllers }
+    }
+
+    /// Let every living opponent choose its turn, once one of the players started moving
+    pub fn steer(&mut self, world: &mut World) {
+        let started = world.snakes()[..world.players()]
+            .iter()
+            .any(|snake| snake.direction().is_some());
+        if !started {
+            return;
+        }
+
+        for (i, controller) in self.controllers.iter_mut().enumerate() {
+            let snake = world.players() + i;
+            if world.snake(snake).is_dead() {
+                continue;
+            }
+            if let Some(dir) = controller.next_direction(world, snake) {
+                world.input(snake, dir);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Opponents {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Opponents")
+            .field("count", &self.controllers.len())
+            .finish()
+    }
+}
+
+/// Turns into a random direction now and then, but never straight into a wall or a snake if it
+/// can avoid it
+#[derive(Clone, Debug)]
+pub struct RandomController {
+    rng: Rng,
+}
+
+impl RandomController {
+    pub fn new(rng: Rng) -> Self {
+        Self { rng }
+    }
+}
+
+impl SnakeController for RandomController {
+    fn next_direction(&mut self, world: &World, snake: usize) -> Option<Direction> {
+        let safe: Vec<_> = safe_directions(world, snake).collect();
+
+        // keep going straight most of the time so the snake actually gets somewhere
+        if let Some(dir) = world.snake(snake).direction() {
+            if safe.contains(&dir) && self.rng.gen_below(4) != 0 {
+                return None;
+            }
+        }
+
+        if safe.is_empty() {
+            return None;
+        }
+        Some(safe[self.rng.gen_below(safe.len() as u32) as usize])
+    }
+}
+
+/// Heads straight for the fruit, but never into a wall or a snake if it can avoid it
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GreedyController;
+
+impl SnakeController for GreedyController {
+    fn next_direction(&mut self, world: &World, snake: usize) -> Option<Direction> {
+        let head = world.snake(snake).head();
+        let fruit = world.fruit()?;
+        let distance = |dir: Direction| {
+            let next = head + dir.to_vector();
+            (next.x - fruit.x).abs() + (next.y - fruit.y).abs()
+        };
+
+        safe_directions(world, snake).min_by_key(|&dir| distance(dir))
+    }
+}
+
+/// Takes the shortest path to the fruit if it can still reach its own tail after eating, and
+/// follows its tail until the way is clear otherwise.
+///
+/// Other snakes are treated as if they stood still.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PathfindingController;
+
+impl SnakeController for PathfindingController {
+    fn next_direction(&mut self, world: &World, snake: usize) -> Option<Direction> {
+        let me = world.snake(snake);
+        let head = me.head();
+        let tail = me.tail();
+        let own: HashSet<_> = me.cells().iter().copied().collect();
+        // the own tail moves on with the next tick unless the snake grows, anything else that is
+        // taken stays taken
+        let taken = |pos: Vector2d| world.is_blocked(pos) && !own.contains(&pos);
+        let tail_stays = me.is_growing();
+        let blocked =
+            |pos: Vector2d| taken(pos) || (own.contains(&pos) && (pos != tail || tail_stays));
+
+        if let Some(fruit) = world.fruit() {
+            if let Some(path) = shortest_path(world, head, fruit, blocked) {
+                // where the snake would be after eating
+                let mut cells = me.cells().clone();
+                for &cell in &path {
+                    cells.push_front(cell);
+                    if cell != fruit {
+                        cells.pop_back();
+                    }
+                }
+
+                let future_tail = cells[cells.len() - 1];
+                let future: HashSet<_> = cells.iter().copied().collect();
+                let future_blocked =
+                    |pos: Vector2d| taken(pos) || (future.contains(&pos) && pos != future_tail);
+                if shortest_path(world, fruit, future_tail, future_blocked).is_some() {
+                    if let Some(dir) = step_towards(world, snake, path[0]) {
+                        return Some(dir);
+                    }
+                }
+            }
+        }
+
+        // stay alive by chasing the own tail, which keeps a way out open. Taking the longest way
+        // there fills the field instead of circling on the same few cells forever.
+        if me.length() > 2 {
+            let to_tail = |dir: Direction| {
+                let next = world.wrap(head + dir.to_vector());
+                // eating here would keep the tail in place
+                if Some(next) == world.fruit() {
+                    return None;
+                }
+                if next == tail {
+                    return Some(1);
+                }
+                let path = shortest_path(world, next, tail, |pos| pos == next || blocked(pos))?;
+                Some(path.len() + 1)
+            };
+            let longest = safe_directions(world, snake)
+                .filter_map(|dir| Some((to_tail(dir)?, dir)))
+                .max_by_key(|&(length, _)| length);
+            if let Some((_, dir)) = longest {
+                return Some(dir);
+            }
+        }
+
+        // trapped, take the move with the most room left
+        safe_directions(world, snake).max_by_key(|&dir| {
+            let next = world.wrap(head + dir.to_vector());
+            reachable_cells(world, next, blocked)
+        })
+    }
+}
+
+/// Plays a fixed sequence of directions, one per tick, and starts over at its end
+#[derive(Clone, Debug)]
+pub struct ScriptedController {
+    script: Vec<Direction>,
+    pos: usize,
+}
+
+impl ScriptedController {
+    pub fn new(script: Vec<Direction>) -> Self {
+        Self { script, pos: 0 }
+    }
+}
+
+impl SnakeController for ScriptedController {
+    fn next_direction(&mut self, _world: &World, _snake: usize) -> Option<Direction> {
+        let dir = self.script.get(self.pos).copied();
+        self.pos = (self.pos + 1) % self.script.len().max(1);
+        dir
+    }
+}
+
+/// The directions a snake can move in next without reversing or dying right away
+fn safe_directions(world: &World, snake: usize) -> impl Iterator<Item = Direction> + '_ {
+    let snake = world.snake(snake);
+    // the tail leaves its cell in the same tick, unless the snake grows by eating
+    let free = move |pos: Vector2d| {
+        pos == snake.tail() && !snake.is_growing() && Some(pos) != world.fruit()
+    };
+    Direction::ALL
+        .into_iter()
+        .filter(move |&dir| Some(dir.opposite()) != snake.direction())
+        .map(move |dir| (dir, world.wrap(snake.head() + dir.to_vector())))
+        .filter(move |&(_, next)| free(next) || !world.is_blocked(next))
+        .map(|(dir, _)| dir)
+}
+
+/// The direction leading from the head of a snake to a neighbouring cell, unless that reverses it
+pub(crate) fn step_towards(world: &World, snake: usize, next: Vector2d) -> Option<Direction> {
+    let snake = world.snake(snake);
+    Direction::ALL
+        .into_iter()
+        .filter(|&dir| Some(dir.opposite()) != snake.direction())
+        .find(|&dir| world.wrap(snake.head() + dir.to_vector()) == next)
+}
+
+/// The cells next to `pos` that lie on the field
+fn neighbours(world: &World, pos: Vector2d) -> impl Iterator<Item = Vector2d> + '_ {
+    let size = world.config().field_size();
+    Direction::ALL
+        .into_iter()
+        .map(move |dir| world.wrap(pos + dir.to_vector()))
+        .filter(move |next| next.is_within(size))
+}
+
+/// Breadth first search for the shortest way from `start` to `goal`, without `start` itself
+fn shortest_path(
+    world: &World,
+    start: Vector2d,
+    goal: Vector2d,
+    blocked: impl Fn(Vector2d) -> bool,
+) -> Option<Vec<Vector2d>> {
+    let mut came_from = HashMap::from([(start, start)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(pos) = queue.pop_front() {
+        if pos == goal && pos != start {
+            let mut path = vec![pos];
+            let mut pos = pos;
+            while came_from[&pos] != start {
+                pos = came_from[&pos];
+                path.push(pos);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for next in neighbours(world, pos) {
+            if !blocked(next) && !came_from.contains_key(&next) {
+                came_from.insert(next, pos);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// The number of free cells connected to `start`, including it
+fn reachable_cells(world: &World, start: Vector2d, blocked: impl Fn(Vector2d) -> bool) -> usize {
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(pos) = queue.pop_front() {
+        for next in neighbours(world, pos) {
+            if !blocked(next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    seen.len()
+}
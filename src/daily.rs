@@ -0,0 +1,140 @@
+use crate::{ArenaKind, Date, GameConfig, Level, Replay, Rng};
+
+/// Mixed into the date a daily seed is derived from
+const SEED_OFFSET: u32 = 0xDA11;
+
+/// The challenge of a UTC date: everyone playing it on that day gets the same seed, and with it the
+/// same arena and fruits
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct DailyChallenge {
+    pub date: Date,
+    /// Only the first finished game of the day enters its high score table, the rest are practice
+    pub one_attempt: bool,
+}
+
+impl DailyChallenge {
+    pub fn new(date: Date) -> Self {
+        Self {
+            date,
+            one_attempt: false,
+        }
+    }
+
+    /// The challenge of the current UTC date
+    pub fn today() -> Self {
+        Self::new(Date::today())
+    }
+
+    /// The seed of every game of the challenge
+    pub fn seed(&self) -> u32 {
+        let date = self.date.year as u32 * 10000 + self.date.month * 100 + self.date.day;
+        let mut rng = Rng::new(date ^ SEED_OFFSET);
+        // the first numbers of nearby seeds are still close to each other
+        rng.gen();
+        rng.gen()
+    }
+
+    /// The kind of arena the challenge is played in, changing from day to day
+    pub fn arena(&self) -> ArenaKind {
+        let kind = Rng::new(self.seed()).gen_below(ArenaKind::ALL.len() as u32);
+        ArenaKind::ALL[kind as usize]
+    }
+
+    /// The rules of the challenge, only the cell size is left to the player
+    pub fn config(&self, cell_size: u32) -> GameConfig {
+        GameConfig {
+            arena: Some(self.arena()),
+            cell_size,
+            ..GameConfig::default()
+        }
+    }
+
+    /// The name of the high score file of the challenge, one per day
+    pub fn high_scores_file(&self) -> String {
+        format!("daily-{}.toml", self.date)
+    }
+
+    /// Check that a replay was recorded in this challenge and played by its rules, its score is
+    /// checked by [`Replay::verify`]
+    pub fn check_rules(&self, replay: &Replay) -> Result<(), String> {
+        if replay.daily != Some(self.date) {
+            return Err(format!(
+                "not a replay of the daily challenge of {}",
+                self.date
+            ));
+        }
+
+        let config = self.config(GameConfig::default().cell_size);
+        let mut expected = Replay::new(self.seed(), &config.for_seed(self.seed()));
+        expected.daily = replay.daily;
+        expected.score = replay.score;
+        expected.ticks = replay.ticks.clone();
+        // levels read back from a replay are named by it, only their text has to match
+        let level = |replay: &Replay| replay.level.as_ref().map(Level::to_text);
+        if level(replay) != level(&expected) {
+            return Err(format!("the arena is not the one of {}", self.date));
+        }
+        expected.level = replay.level.clone();
+        if *replay != expected {
+            return Err(format!("not played by the rules of {}", self.date));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, Game, GameState, HighScores};
+
+    /// Finish the current game by running into the nearest wall
+    fn crash(game: &mut Game) {
+        while game.state() != GameState::GameOver {
+            game.input(0, Direction::Up);
+            game.update();
+        }
+    }
+
+    #[test]
+    fn days_share_their_challenge() {
+        let day = DailyChallenge::new("2026-10-17".parse().unwrap());
+        let next = DailyChallenge::new("2026-10-18".parse().unwrap());
+        assert_eq!(day.seed(), DailyChallenge::new(day.date).seed());
+        assert_ne!(day.seed(), next.seed());
+        assert_eq!(day.config(40), day.config(40));
+        assert!("2026-13-01".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn replays_prove_the_daily_score() {
+        let mut challenge = DailyChallenge::new("2026-10-17".parse().unwrap());
+        challenge.one_attempt = true;
+        let mut game = Game::daily(challenge, 40, HighScores::in_memory());
+        game.start();
+        game.toggle_autopilot();
+        for _ in 0..300 {
+            game.update();
+        }
+        game.toggle_autopilot();
+        crash(&mut game);
+
+        let replay = game.replay().clone();
+        assert!(replay.score > 0);
+        assert_eq!(replay.verify(), Ok(()));
+        let mut cheated = replay.clone();
+        cheated.score += 10;
+        assert!(cheated.verify().is_err());
+        let mut other_day = replay.clone();
+        other_day.daily = Some("2026-10-18".parse().unwrap());
+        assert!(other_day.verify().is_err());
+
+        // the autopilot kept the first game out of the table, the second one is the attempt
+        assert!(game.attempt_counts());
+        game.restart(false);
+        assert_eq!(game.seed(), challenge.seed());
+        crash(&mut game);
+        assert!(!game.attempt_counts());
+        assert_eq!(game.high_scores().entries().len(), 1);
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A calendar date in the proleptic gregorian calendar
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// The current date in UTC
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        Self::from_unix_days((secs / (24 * 60 * 60)) as i64)
+    }
+
+    /// The date a number of days after 1970-01-01
+    pub fn from_unix_days(days: i64) -> Self {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400) as i32 + (month <= 2) as i32;
+
+        Self { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parse a date formatted as `YYYY-MM-DD`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date {:?}", s);
+        let mut parts = s
+            .splitn(3, '-')
+            .map(|part| part.parse::<i32>().map_err(|_| invalid()));
+        let (year, month, day) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(year), Some(month), Some(day)) => (year?, month? as u32, day? as u32),
+            _ => return Err(invalid()),
+        };
+        if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+            return Err(invalid());
+        }
+
+        Ok(Self { year, month, day })
+    }
+}
+
+/// The number of days of a month, counted from 1 for January
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_are_checked_against_their_month() {
+        assert_eq!(
+            "2026-01-31".parse(),
+            Ok(Date {
+                year: 2026,
+                month: 1,
+                day: 31
+            })
+        );
+        assert!("2026-04-31".parse::<Date>().is_err());
+        assert!("2026-02-31".parse::<Date>().is_err());
+        assert!("2026-02-29".parse::<Date>().is_err());
+        assert!("2028-02-29".parse::<Date>().is_ok());
+        assert!("1900-02-29".parse::<Date>().is_err());
+        assert!("2000-02-29".parse::<Date>().is_ok());
+        assert!("2026-10-00".parse::<Date>().is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+/// A preset for how fast the snake starts and how quickly it speeds up
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Insane,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Insane => "insane",
+        }
+    }
+
+    /// The difficulty with the given [`name`](Self::name)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name() == name)
+    }
+
+    /// The next harder difficulty, wrapping back to the easiest one
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Insane,
+            Difficulty::Insane => Difficulty::Easy,
+        }
+    }
+
+    pub fn speed_curve(self) -> SpeedCurve {
+        let (start, step, every, max) = match self {
+            Difficulty::Easy => (6, 1, 5, 12),
+            Difficulty::Normal => (10, 1, 4, 18),
+            Difficulty::Hard => (14, 1, 3, 24),
+            Difficulty::Insane => (20, 2, 2, 40),
+        };
+        SpeedCurve {
+            start,
+            step,
+            every,
+            max,
+        }
+    }
+}
+
+/// How the tick rate rises with the number of fruits eaten
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpeedCurve {
+    /// The ticks per second at the start of a game
+    pub start: u32,
+    /// The ticks per second added every `every` fruits
+    pub step: u32,
+    pub every: u32,
+    /// The ticks per second the rate never rises above
+    pub max: u32,
+}
+
+impl SpeedCurve {
+    const MAX_TICK_RATE: u32 = 120;
+
+    /// The ticks per second after eating the given number of fruits
+    pub fn tick_rate(&self, fruits: u32) -> u32 {
+        let steps = fruits / self.every;
+        self.start
+            .saturating_add(steps.saturating_mul(self.step))
+            .min(self.max)
+    }
+
+    /// Check that the curve starts moving and stays below a sane tick rate
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start == 0 || self.every == 0 {
+            return Err("speed start and every must be at least 1".to_owned());
+        }
+        if self.max < self.start || self.max > Self::MAX_TICK_RATE {
+            return Err(format!(
+                "speed max {} is not within {} and {}",
+                self.max,
+                self.start,
+                Self::MAX_TICK_RATE
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tick_rates_rise_in_steps_up_to_the_max() {
+        let curve = Difficulty::Normal.speed_curve();
+        assert_eq!(curve.tick_rate(0), 10);
+        assert_eq!(curve.tick_rate(3), 10);
+        assert_eq!(curve.tick_rate(4), 11);
+        assert_eq!(curve.tick_rate(9), 12);
+        assert_eq!(curve.tick_rate(1000), 18);
+        let steep = SpeedCurve {
+            start: 1,
+            step: u32::MAX,
+            every: 1,
+            max: 120,
+        };
+        assert_eq!(steep.tick_rate(u32::MAX), 120);
+    }
+
+    #[test]
+    fn curves_must_move_and_stay_sane() {
+        for difficulty in Difficulty::ALL {
+            assert_eq!(difficulty.speed_curve().validate(), Ok(()));
+        }
+        let curve = Difficulty::Normal.speed_curve();
+        assert!(SpeedCurve { start: 0, ..curve }.validate().is_err());
+        assert!(SpeedCurve { every: 0, ..curve }.validate().is_err());
+        assert!(SpeedCurve { max: 9, ..curve }.validate().is_err());
+        assert!(SpeedCurve { max: 121, ..curve }.validate().is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Vector2d;
+
+/// The fruit table games are played with unless the config brings its own
+const DEFAULT_TABLE: &str = include_str!("../fruits.toml");
+
+/// What eating a fruit does, on top of the points every fruit but poison is worth
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FruitEffect {
+    /// The snake grows by this many cells over the next ticks
+    Grow(u32),
+    /// Extra points
+    Bonus(u32),
+    /// The snake loses this many cells at its tail, but never its head
+    Shrink(u32),
+    /// The tick rate changes by `change` ticks per second for the next `ticks` ticks
+    Speed { change: i32, ticks: u32 },
+    /// The snake dies
+    Poison,
+}
+
+/// A kind of fruit in the fruit table of a [`GameConfig`](crate::GameConfig).
+///
+/// The first kind of a table is the regular fruit, one of which is always on the field. Whenever
+/// it is eaten, each of the other kinds has a chance to appear next to the new one.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FruitKind {
+    pub name: String,
+    /// The color the fruit is drawn in, as RGB
+    pub color: [u8; 3],
+    /// One in this many regular fruits is joined by one of this kind, 0 for never. Ignored for
+    /// the regular fruit.
+    #[serde(default)]
+    pub rarity: u32,
+    /// The ticks the fruit stays on the field before it disappears, until it is eaten if missing.
+    /// A regular fruit reappears elsewhere.
+    #[serde(default)]
+    pub lifetime: Option<u32>,
+    pub effect: FruitEffect,
+}
+
+impl FruitKind {
+    /// The table of classic snake, with a single kind of fruit growing the snake by one
+    pub fn classic_table() -> Vec<FruitKind> {
+        vec![FruitKind {
+            name: "apple".to_owned(),
+            color: [0xFF, 0, 0],
+            rarity: 0,
+            lifetime: None,
+            effect: FruitEffect::Grow(1),
+        }]
+    }
+
+    /// The table games are played with unless the config brings its own
+    pub fn default_table() -> Vec<FruitKind> {
+        let table: FruitTable = toml::from_str(DEFAULT_TABLE).unwrap();
+        table.fruits
+    }
+
+    /// Check that a table has a regular fruit the snake can eat
+    pub(crate) fn validate_table(table: &[FruitKind]) -> Result<(), String> {
+        let regular = table.first().ok_or("the fruit table is empty")?;
+        if regular.effect == FruitEffect::Poison {
+            return Err(format!("the regular fruit {} is poison", regular.name));
+        }
+        if regular.lifetime == Some(0) {
+            return Err(format!("the regular fruit {} never appears", regular.name));
+        }
+
+        Ok(())
+    }
+}
+
+/// The file format of the default fruit table
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FruitTable {
+    fruits: Vec<FruitKind>,
+}
+
+/// A fruit lying on the field
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Fruit {
+    pub pos: Vector2d,
+    /// The index of its kind in the fruit table
+    pub kind: usize,
+    /// The tick the fruit disappears at, `None` if it stays until eaten
+    pub expires: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_fruit_table_is_valid() {
+        let table = FruitKind::default_table();
+        assert_eq!(FruitKind::validate_table(&table), Ok(()));
+        assert_eq!(table[0].effect, FruitEffect::Grow(1));
+        assert!(FruitKind::validate_table(&[]).is_err());
+        assert!(FruitKind::validate_table(&table[table.len() - 1..]).is_err());
+    }
+}
@@ -0,0 +1,440 @@
+use std::time::Duration;
+
+use crate::{
+    ArenaKind, Autopilot, DailyChallenge, Date, Direction, GameConfig, HighScore, HighScores,
+    Level, Opponents, Replay, Rng, RoundResult, Scoreboard, SnakeController, StepOutcome, Theme,
+    Tileset, WallMode, World,
+};
+
+/// The screen the game is currently on
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum GameState {
+    Title,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+/// A [`World`] wrapped in the state machine of a playable game
+#[derive(Debug)]
+pub struct Game {
+    state: GameState,
+    world: World,
+    /// Steer the computer controlled snakes of the world
+    opponents: Opponents,
+    /// Steers the first snake instead of the player while switched on
+    autopilot: Option<Autopilot>,
+    /// Whether the autopilot played any part of the current world, which keeps it out of the
+    /// high score table
+    assisted: bool,
+    seed: u32,
+    config: GameConfig,
+    /// The challenge of the day, whose seed and rules every game is played with
+    daily: Option<DailyChallenge>,
+    /// The time spent playing the current world, counted in ticks
+    elapsed: Duration,
+    /// The recording of the current world, single player games only
+    replay: Replay,
+    /// The rounds won in a versus match
+    scoreboard: Scoreboard,
+    high_scores: HighScores,
+    /// The rank the last finished game reached in the high score table
+    last_rank: Option<usize>,
+    /// Generates the seeds of fresh games
+    seeds: Rng,
+    /// The sprites the world is drawn with, scaled to the cell size, plain squares without
+    tileset: Option<Tileset>,
+    /// The colors everything is drawn in
+    theme: Theme,
+}
+
+impl Game {
+    pub fn new(config: GameConfig, mut seeds: Rng, high_scores: HighScores) -> Self {
+        let seed = seeds.gen();
+        let world_config = config.for_seed(seed);
+        Self {
+            state: GameState::Title,
+            world: World::new(&world_config, Rng::new(seed)),
+            opponents: Opponents::new(&world_config, seed),
+            autopilot: None,
+            assisted: false,
+            seed,
+            daily: None,
+            elapsed: Duration::ZERO,
+            replay: Replay::new(seed, &world_config),
+            scoreboard: Scoreboard::new(config.players as usize),
+            config,
+            high_scores,
+            last_rank: None,
+            seeds,
+            tileset: None,
+            theme: Theme::default(),
+        }
+    }
+
+    /// A game of the daily challenge, which keeps the seed of the day for every game
+    pub fn daily(challenge: DailyChallenge, cell_size: u32, high_scores: HighScores) -> Self {
+        let seed = challenge.seed();
+        let mut game = Self::new(challenge.config(cell_size), Rng::new(seed), high_scores);
+        game.daily = Some(challenge);
+        game.seed = seed;
+        game.reset_world();
+        game
+    }
+
+    /// Draw the world with the sprites of a tileset instead of plain squares
+    pub fn set_tileset(&mut self, tileset: Option<Tileset>) {
+        self.tileset = tileset.map(|tileset| tileset.scaled(self.config.cell_size));
+    }
+
+    pub fn tileset(&self) -> Option<&Tileset> {
+        self.tileset.as_ref()
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Leave the title screen and start playing
+    pub fn start(&mut self) {
+        if self.state == GameState::Title {
+            self.state = GameState::Playing;
+        }
+    }
+
+    /// Reset the world and start playing again, either with the last seed or a fresh one.
+    ///
+    /// In a versus match this starts the next round, or a new match once it was won.
+    pub fn restart(&mut self, same_seed: bool) {
+        if !same_seed && self.daily.is_none() {
+            self.seed = self.seeds.gen();
+        }
+        if self.scoreboard.match_winner().is_some() {
+            self.scoreboard = Scoreboard::new(self.config.players as usize);
+        }
+
+        self.reset_world();
+        self.last_rank = None;
+        self.state = GameState::Playing;
+    }
+
+    /// Switch between solid and wrapping walls, only possible on the title screen
+    pub fn toggle_wall_mode(&mut self) {
+        if self.can_configure() {
+            self.config.walls = self.config.walls.toggled();
+            self.reset_world();
+        }
+    }
+
+    /// Switch between a single player game and a versus match, only possible on the title screen
+    pub fn toggle_versus(&mut self) {
+        if self.can_configure() {
+            self.config.players = if self.is_versus() { 1 } else { 2 };
+            self.scoreboard = Scoreboard::new(self.config.players as usize);
+            self.reset_world();
+        }
+    }
+
+    /// Switch to the next difficulty preset, only possible on the title screen
+    pub fn cycle_difficulty(&mut self) {
+        if self.can_configure() {
+            self.config.difficulty = self.config.difficulty.next();
+            // a custom curve would hide the switch
+            self.config.speed = None;
+            self.reset_world();
+        }
+    }
+
+    /// Switch to the next bundled level, or back to the open field after the last one. Only
+    /// possible on the title screen.
+    pub fn cycle_level(&mut self) {
+        if self.can_configure() {
+            let current = self
+                .config
+                .level
+                .as_ref()
+                .map(|level| level.source().to_owned());
+            let mut names = Level::bundled_names();
+            let next = match current {
+                Some(current) => names.skip_while(|&name| name != current).nth(1),
+                None => names.next(),
+            };
+            self.config.level = next.and_then(Level::bundled);
+            self.config.arena = None;
+            self.reset_world();
+        }
+    }
+
+    /// Switch to the next kind of random arena, or back to no arena after the last one. Only
+    /// possible on the title screen.
+    pub fn cycle_arena(&mut self) {
+        if self.can_configure() {
+            let mut kinds = ArenaKind::ALL.into_iter();
+            self.config.arena = match self.config.arena {
+                Some(current) => kinds.skip_while(|&kind| kind != current).nth(1),
+                None => kinds.next(),
+            };
+            self.config.level = None;
+            self.reset_world();
+        }
+    }
+
+    /// Switch to the next number of opponents, only possible on the title screen
+    pub fn cycle_opponents(&mut self) {
+        if self.can_configure() {
+            self.config.opponents = (self.config.opponents + 1) % (GameConfig::MAX_OPPONENTS + 1);
+            self.reset_world();
+        }
+    }
+
+    /// Switch to the next strategy of the opponents, only possible on the title screen
+    pub fn cycle_opponent_strategy(&mut self) {
+        if self.can_configure() {
+            self.config.opponent_strategy = self.config.opponent_strategy.next();
+            self.reset_world();
+        }
+    }
+
+    /// Hand the snake to the autopilot or take it back, single player games only
+    pub fn toggle_autopilot(&mut self) {
+        let running = matches!(self.state, GameState::Playing | GameState::Paused);
+        if !running || self.is_versus() {
+            return;
+        }
+
+        self.autopilot = match self.autopilot {
+            Some(_) => None,
+            None => Some(Autopilot::new(&self.world)),
+        };
+        self.assisted |= self.autopilot.is_some();
+    }
+
+    /// Whether the rules can be changed, only on the title screen and never in a daily challenge
+    fn can_configure(&self) -> bool {
+        self.state == GameState::Title && self.daily.is_none()
+    }
+
+    /// Create a fresh world from the current seed and config
+    fn reset_world(&mut self) {
+        let config = self.config.for_seed(self.seed);
+        self.world = World::new(&config, Rng::new(self.seed));
+        self.opponents = Opponents::new(&config, self.seed);
+        // an autopilot left on keeps playing the next game, the field may have changed
+        if self.autopilot.is_some() && !self.is_versus() {
+            self.autopilot = Some(Autopilot::new(&self.world));
+        } else {
+            self.autopilot = None;
+        }
+        self.assisted = self.autopilot.is_some();
+        self.elapsed = Duration::ZERO;
+        self.replay = Replay::new(self.seed, &config);
+        self.replay.daily = self.daily.map(|daily| daily.date);
+    }
+
+    /// Pause a running game, does nothing on the other screens
+    pub fn pause(&mut self) {
+        if self.state == GameState::Playing {
+            self.state = GameState::Paused;
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.state = match self.state {
+            GameState::Playing => GameState::Paused,
+            GameState::Paused => GameState::Playing,
+            state => state,
+        };
+    }
+
+    /// Steer the snake of a player, unless the autopilot has it
+    pub fn input(&mut self, player: usize, dir: Direction) {
+        if player == 0 && self.autopilot.is_some() {
+            return;
+        }
+        if self.state == GameState::Playing {
+            self.world.input(player, dir);
+        }
+    }
+
+    /// Advance the world by one tick if the game is running
+    pub fn update(&mut self) -> StepOutcome {
+        if self.state != GameState::Playing {
+            return StepOutcome::Idle;
+        }
+
+        self.tick()
+    }
+
+    /// Advance the world by exactly one tick while paused, for debugging
+    pub fn step(&mut self) -> StepOutcome {
+        if self.state != GameState::Paused {
+            return StepOutcome::Idle;
+        }
+
+        self.tick()
+    }
+
+    fn tick(&mut self) -> StepOutcome {
+        // eating speeds up the following ticks, not this one
+        let tick_duration = self.tick_duration();
+        if let Some(autopilot) = &mut self.autopilot {
+            if let Some(dir) = autopilot.next_direction(&self.world, 0) {
+                self.world.input(0, dir);
+            }
+        }
+        self.opponents.steer(&mut self.world);
+        let outcome = self.world.update();
+        if outcome != StepOutcome::Idle {
+            self.elapsed += tick_duration;
+        }
+        // a field without room for a fruit is won before the snake ever moves
+        if let Some(dir) = self.world.snake(0).direction() {
+            if outcome != StepOutcome::Idle && !self.is_versus() {
+                self.replay.record(dir);
+            }
+        }
+
+        if outcome.is_over() {
+            self.state = GameState::GameOver;
+            if self.is_versus() {
+                self.scoreboard.record(RoundResult::of(&self.world));
+                return outcome;
+            }
+
+            let snake = self.world.snake(0);
+            self.replay.score = snake.score();
+            if self.assisted || !self.attempt_counts() {
+                return outcome;
+            }
+            self.last_rank = self.high_scores.insert(HighScore {
+                score: snake.score(),
+                length: snake.length() as u32,
+                duration_ms: self.elapsed.as_millis() as u64,
+                seed: self.seed,
+                mode: self.mode(),
+                difficulty: self.config.difficulty,
+                date: Date::today().to_string(),
+            });
+        }
+
+        outcome
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// The time between two ticks at the current speed
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_secs_f64(1f64 / self.world.tick_rate() as f64)
+    }
+
+    /// The time spent playing the current world, pauses and waiting for the first turn excluded
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The recording of the current world, complete once the game is over
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Whether two players play against each other, versus matches are not recorded
+    pub fn is_versus(&self) -> bool {
+        self.config.players > 1
+    }
+
+    /// The challenge of the day, if this is a game of it
+    pub fn daily_challenge(&self) -> Option<DailyChallenge> {
+        self.daily
+    }
+
+    /// Whether the next finished game enters the high score table, in a daily challenge where one
+    /// attempt counts only the first one does
+    pub fn attempt_counts(&self) -> bool {
+        match self.daily {
+            Some(daily) if daily.one_attempt => self.high_scores.entries().is_empty(),
+            _ => true,
+        }
+    }
+
+    /// Whether the autopilot currently steers the snake
+    pub fn is_autopilot(&self) -> bool {
+        self.autopilot.is_some()
+    }
+
+    pub fn scoreboard(&self) -> &Scoreboard {
+        &self.scoreboard
+    }
+
+    /// The best score so far, including the game currently played
+    pub fn high_score(&self) -> u32 {
+        self.high_scores.best().max(self.world.snake(0).score())
+    }
+
+    /// The table finished games are recorded in
+    pub fn high_scores(&self) -> &HighScores {
+        &self.high_scores
+    }
+
+    /// The rank the last finished game reached in the high score table
+    pub fn last_rank(&self) -> Option<usize> {
+        self.last_rank
+    }
+
+    /// The name of the game mode recorded in high scores, including the level or the kind of
+    /// random arena if there is one
+    pub fn mode(&self) -> String {
+        let field = match (&self.config.level, self.config.arena) {
+            (_, Some(arena)) => Some(format!("{} arena", arena.name())),
+            (Some(level), None) => Some(level.source().to_owned()),
+            (None, None) => None,
+        };
+        match (field, self.config.walls) {
+            (None, WallMode::Solid) => "classic".to_owned(),
+            (None, WallMode::Wrap) => "wrap".to_owned(),
+            (Some(field), WallMode::Solid) => field,
+            (Some(field), WallMode::Wrap) => format!("{} wrap", field),
+        }
+    }
+
+    /// The seed the current world was created from
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vector2d;
+
+    #[test]
+    fn a_field_without_room_for_fruit_is_won_at_once() {
+        // parsing and validation reject such levels, `Game` must not panic on them regardless
+        let mut walls = vec![true; 16];
+        walls[0] = false;
+        let level = Level::from_walls("full".into(), "full".into(), Vector2d::new(4, 4), walls);
+        let config = GameConfig {
+            level: Some(level),
+            ..GameConfig::default()
+        };
+        let mut game = Game::new(config, Rng::new(1), HighScores::in_memory());
+        game.start();
+        assert_eq!(game.update(), StepOutcome::Won);
+        assert_eq!(game.state(), GameState::GameOver);
+        assert!(game.replay().ticks.is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{Rng, Vector2d};
+
+/// Marks a cell that is not in the free list
+const OCCUPIED: usize = usize::MAX;
+
+/// Which cells of the field are occupied, with the free ones kept in a list for O(1) sampling
+#[derive(Clone, Debug)]
+pub(crate) struct Grid {
+    size: Vector2d,
+    /// The position of every cell in `free`, or [`OCCUPIED`]
+    slots: Vec<usize>,
+    free: Vec<Vector2d>,
+}
+
+impl Grid {
+    /// A grid of the given size with all cells free
+    pub fn new(size: Vector2d) -> Self {
+        let free: Vec<_> = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| Vector2d::new(x, y)))
+            .collect();
+        Self {
+            size,
+            slots: (0..free.len()).collect(),
+            free,
+        }
+    }
+
+    /// Whether the cell is taken, cells outside the grid count as occupied
+    pub fn is_occupied(&self, pos: Vector2d) -> bool {
+        !pos.is_within(self.size) || self.slots[self.index(pos)] == OCCUPIED
+    }
+
+    /// Take a cell out of the free list, does nothing if it already is occupied
+    pub fn occupy(&mut self, pos: Vector2d) {
+        let index = self.index(pos);
+        let slot = self.slots[index];
+        if slot == OCCUPIED {
+            return;
+        }
+
+        // move the last free cell into the gap
+        self.free.swap_remove(slot);
+        if let Some(&moved) = self.free.get(slot) {
+            let moved = self.index(moved);
+            self.slots[moved] = slot;
+        }
+        self.slots[index] = OCCUPIED;
+    }
+
+    /// Put a cell back into the free list, does nothing if it already is free
+    pub fn release(&mut self, pos: Vector2d) {
+        let index = self.index(pos);
+        if self.slots[index] != OCCUPIED {
+            return;
+        }
+
+        self.slots[index] = self.free.len();
+        self.free.push(pos);
+    }
+
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// A free cell chosen uniformly at random, `None` if the grid is full
+    pub fn random_free(&self, rng: &mut Rng) -> Option<Vector2d> {
+        if self.free.is_empty() {
+            return None;
+        }
+
+        Some(self.free[rng.gen_below(self.free.len() as u32) as usize])
+    }
+
+    fn index(&self, pos: Vector2d) -> usize {
+        (pos.y * self.size.x + pos.x) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Check that the free list and the slots agree with each other
+    fn check(grid: &Grid) {
+        for (slot, &pos) in grid.free.iter().enumerate() {
+            assert_eq!(grid.slots[grid.index(pos)], slot);
+        }
+        let occupied = grid.slots.iter().filter(|&&slot| slot == OCCUPIED).count();
+        assert_eq!(occupied + grid.free_count(), grid.slots.len());
+    }
+
+    #[test]
+    fn occupied_cells_leave_the_free_list() {
+        let mut grid = Grid::new(Vector2d::new(3, 2));
+        let first = Vector2d::new(0, 0);
+        let last = Vector2d::new(2, 1);
+        grid.occupy(first);
+        grid.occupy(first);
+        check(&grid);
+        assert!(grid.is_occupied(first));
+        assert_eq!(grid.free_count(), 5);
+        // the last cell filled the gap of the first one
+        assert_eq!(grid.free[0], last);
+
+        grid.occupy(last);
+        grid.release(first);
+        grid.release(first);
+        check(&grid);
+        assert!(!grid.is_occupied(first));
+        assert!(grid.is_occupied(last));
+        assert!(grid.is_occupied(Vector2d::new(3, 0)));
+        assert_eq!(grid.free_count(), 5);
+    }
+
+    #[test]
+    fn random_cells_are_free_until_the_grid_is_full() {
+        let mut grid = Grid::new(Vector2d::new(4, 4));
+        let mut rng = Rng::new(1);
+        while let Some(pos) = grid.random_free(&mut rng) {
+            assert!(!grid.is_occupied(pos));
+            grid.occupy(pos);
+            check(&grid);
+        }
+        assert_eq!(grid.free_count(), 0);
+    }
+
+    #[test]
+    fn random_cells_do_not_follow_each_other_in_a_cycle() {
+        let grid = Grid::new(Vector2d::new(4, 1));
+        let mut rng = Rng::new(1);
+        let mut pick = || grid.random_free(&mut rng).unwrap().x as usize;
+        // every cell follows every other one about equally often
+        let mut pairs = [0; 16];
+        for _ in 0..16000 {
+            pairs[pick() * 4 + pick()] += 1;
+        }
+        assert!(
+            pairs.iter().all(|&count| (850..1150).contains(&count)),
+            "{:?}",
+            pairs
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    cmp::Reverse,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Difficulty;
+
+/// The version of the high score file format written by this build
+const FILE_VERSION: u32 = 1;
+
+/// A single finished game in the high score table
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub length: u32,
+    /// The time spent playing in milliseconds
+    pub duration_ms: u64,
+    pub seed: u32,
+    pub mode: String,
+    /// Missing in tables written before difficulties existed, which played like normal
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// The UTC date the game ended on, formatted as `YYYY-MM-DD`
+    pub date: String,
+}
+
+impl HighScore {
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighScoreFile {
+    version: u32,
+    #[serde(default)]
+    entries: Vec<HighScore>,
+}
+
+/// The best games played, sorted by score and optionally backed by a file
+#[derive(Clone, Debug, Default)]
+pub struct HighScores {
+    path: Option<PathBuf>,
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// How many entries are kept in the table
+    pub const MAX_ENTRIES: usize = 10;
+
+    /// A table that is never saved to disk
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the table from `path`, saving will write back to it.
+    ///
+    /// A missing file results in an empty table. A file that can not be parsed is moved aside to
+    /// `<path>.corrupt` with a warning, so it is not overwritten by the next save.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = match Self::read(&path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                let mut backup = path.clone().into_os_string();
+                backup.push(".corrupt");
+                eprintln!(
+                    "Could not read high scores from {}, moving it to {}: {}",
+                    path.display(),
+                    Path::new(&backup).display(),
+                    e
+                );
+                if let Err(e) = fs::rename(&path, &backup) {
+                    eprintln!("Could not move corrupted high score file: {}", e);
+                }
+                Vec::new()
+            }
+        };
+
+        let mut me = Self {
+            path: Some(path),
+            entries,
+        };
+        me.sort();
+        me
+    }
+
+    fn read(path: &Path) -> io::Result<Vec<HighScore>> {
+        let text = fs::read_to_string(path)?;
+        let file: HighScoreFile =
+            toml::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if file.version > FILE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported version {}", file.version),
+            ));
+        }
+
+        Ok(file.entries)
+    }
+
+    /// Atomically write the table back to the file it was loaded from
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = HighScoreFile {
+            version: FILE_VERSION,
+            entries: self.entries.clone(),
+        };
+        let text = toml::to_string(&file).map_err(io::Error::other)?;
+
+        // write to a temporary file first so a crash never leaves a half written table behind
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".tmp");
+        let mut file = File::create(&tmp)?;
+        file.write_all(text.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    }
+
+    /// Add a finished game, returns its rank if it made it into the table
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|e| e.score < entry.score)
+            .unwrap_or(self.entries.len());
+        if rank >= Self::MAX_ENTRIES {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(Self::MAX_ENTRIES);
+        Some(rank)
+    }
+
+    /// The entries from best to worst
+    pub fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// The best score in the table
+    pub fn best(&self) -> u32 {
+        self.entries.first().map(|e| e.score).unwrap_or(0)
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by_key(|e| Reverse(e.score));
+        self.entries.truncate(Self::MAX_ENTRIES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// An empty directory of its own for each test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("snake-pixels-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry(score: u32) -> HighScore {
+        HighScore {
+            score,
+            length: 1,
+            duration_ms: 0,
+            seed: score,
+            mode: "solid".to_owned(),
+            difficulty: Difficulty::Normal,
+            date: "2026-10-17".to_owned(),
+        }
+    }
+
+    fn scores(table: &HighScores) -> Vec<u32> {
+        table.entries().iter().map(|e| e.score).collect()
+    }
+
+    #[test]
+    fn only_the_best_games_are_ranked() {
+        let mut table = HighScores::in_memory();
+        assert_eq!(table.insert(entry(20)), Some(0));
+        assert_eq!(table.insert(entry(30)), Some(0));
+        // ties rank below the games that got there first
+        assert_eq!(table.insert(entry(20)), Some(2));
+        for _ in 3..HighScores::MAX_ENTRIES {
+            table.insert(entry(10));
+        }
+        assert_eq!(table.insert(entry(10)), None);
+        assert_eq!(table.insert(entry(15)), Some(3));
+        assert_eq!(table.entries().len(), HighScores::MAX_ENTRIES);
+        assert_eq!(scores(&table)[..5], [30, 20, 20, 15, 10]);
+        assert_eq!(table.best(), 30);
+    }
+
+    #[test]
+    fn tables_are_saved_through_a_temporary_file() {
+        let dir = temp_dir("save");
+        let path = dir.join("scores/highscores.toml");
+        let mut table = HighScores::open(&path);
+        assert!(table.entries().is_empty());
+        table.insert(entry(10));
+        table.insert(entry(40));
+        table.save().unwrap();
+
+        assert!(!dir.join("scores/highscores.toml.tmp").exists());
+        assert_eq!(scores(&HighScores::open(&path)), [40, 10]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unreadable_tables_are_moved_aside() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join("highscores.toml");
+        fs::write(&path, "not a high score table").unwrap();
+        assert!(HighScores::open(&path).entries().is_empty());
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("highscores.toml.corrupt")).unwrap(),
+            "not a high score table"
+        );
+
+        // tables of newer builds are kept as well instead of being overwritten
+        let newer = format!("version = {}\n", FILE_VERSION + 1);
+        fs::write(&path, &newer).unwrap();
+        assert!(HighScores::read(&path).is_err());
+        assert!(HighScores::open(&path).entries().is_empty());
+        assert_eq!(
+            fs::read_to_string(dir.join("highscores.toml.corrupt")).unwrap(),
+            newer
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+/// A fixed timestep: real time is collected in an accumulator and paid out in whole ticks
+#[derive(Clone, Debug)]
+pub struct Interval {
+    last: Instant,
+    /// Time that passed but was not yet consumed by a tick
+    accumulator: Duration,
+    frame_duration: Duration,
+}
+
+impl Interval {
+    /// The most ticks that are caught up on after a stall, anything beyond is dropped
+    const MAX_CATCH_UP: u32 = 4;
+
+    pub fn new(fps: u32) -> Self {
+        Self {
+            last: Instant::now(),
+            accumulator: Duration::ZERO,
+            frame_duration: Duration::from_secs_f64(1f64 / fps as f64),
+        }
+    }
+
+    /// Change the length of ticks, keeping how far into the current tick we are
+    pub fn set_fps(&mut self, fps: u32) {
+        let frame_duration = Duration::from_secs_f64(1f64 / fps as f64);
+        if frame_duration == self.frame_duration {
+            return;
+        }
+
+        self.accumulator = frame_duration.mul_f64(self.alpha() as f64);
+        self.frame_duration = frame_duration;
+    }
+
+    /// Add the time passed since the last call to the accumulator
+    pub fn advance(&mut self) {
+        let now = Instant::now();
+        self.accumulate(now - self.last);
+        self.last = now;
+    }
+
+    /// Add time to the accumulator, dropping what is beyond the ticks caught up on
+    fn accumulate(&mut self, elapsed: Duration) {
+        self.accumulator += elapsed;
+        self.accumulator = self
+            .accumulator
+            .min(self.frame_duration * Self::MAX_CATCH_UP);
+    }
+
+    /// Consume one tick from the accumulator, call until it returns false
+    pub fn tick(&mut self) -> bool {
+        if self.accumulator < self.frame_duration {
+            return false;
+        }
+
+        self.accumulator -= self.frame_duration;
+        true
+    }
+
+    /// How far into the next tick we are, from 0 up to 1
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f64() / self.frame_duration.as_secs_f64()).min(1.0) as f32
+    }
+
+    /// The point in time at which the next tick is due
+    pub fn deadline(&self) -> Instant {
+        self.last + self.frame_duration.saturating_sub(self.accumulator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(interval: &mut Interval) -> u32 {
+        let mut ticks = 0;
+        while interval.tick() {
+            ticks += 1;
+        }
+        ticks
+    }
+
+    #[test]
+    fn time_is_paid_out_in_whole_ticks() {
+        let mut interval = Interval::new(10);
+        interval.accumulate(Duration::from_millis(50));
+        assert_eq!(ticks(&mut interval), 0);
+        assert!((interval.alpha() - 0.5).abs() < 1e-6);
+        interval.accumulate(Duration::from_millis(175));
+        assert_eq!(ticks(&mut interval), 2);
+        assert!((interval.alpha() - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn stalls_are_caught_up_on_only_partly() {
+        let mut interval = Interval::new(10);
+        interval.accumulate(Duration::from_secs(10));
+        assert_eq!(ticks(&mut interval), Interval::MAX_CATCH_UP);
+        assert_eq!(interval.alpha(), 0.0);
+    }
+
+    #[test]
+    fn changing_the_rate_keeps_the_progress_into_the_tick() {
+        let mut interval = Interval::new(10);
+        interval.accumulate(Duration::from_millis(75));
+        interval.set_fps(20);
+        assert!((interval.alpha() - 0.75).abs() < 1e-6);
+        assert_eq!(ticks(&mut interval), 0);
+        interval.accumulate(Duration::from_millis(15));
+        assert_eq!(ticks(&mut interval), 1);
+    }
+}
@@ -0,0 +1,271 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{bundled, ArenaKind, Vector2d};
+
+/// The levels shipped with the game, by the name configs refer to them with
+const BUNDLED: [(&str, &str); 4] = [
+    ("pillars", include_str!("../levels/pillars.txt")),
+    ("cross", include_str!("../levels/cross.txt")),
+    ("rooms", include_str!("../levels/rooms.txt")),
+    ("tunnel", include_str!("../levels/tunnel.txt")),
+];
+
+/// Separates the optional metadata header from the grid of a level file
+const HEADER_END: &str = "---";
+
+/// A field with walls inside it, read from a plain text level file.
+///
+/// The file is an ASCII grid with one character per cell: `#` for a wall, `.` or a space for a
+/// free cell and `S` for the start of a snake, in reading order. It may begin with a TOML header
+/// giving the `name` and `author` of the level, ended by a line of `---`. Short rows are filled up
+/// with free cells.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Level {
+    name: String,
+    author: Option<String>,
+    /// The bundled name or path the level was found by, which configs refer to it with
+    source: String,
+    size: Vector2d,
+    /// Whether each cell is a wall, indexed by `y * width + x`
+    walls: Vec<bool>,
+    starts: Vec<Vector2d>,
+}
+
+/// The metadata header of a level file
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LevelHeader {
+    name: Option<String>,
+    author: Option<String>,
+}
+
+impl Level {
+    /// Parse the text of a level file, `source` names the level if the header does not
+    pub fn parse(text: &str, source: &str) -> Result<Self, String> {
+        let lines: Vec<_> = text.trim_end().lines().map(str::trim_end).collect();
+        let (header, rows) = match lines.iter().position(|&line| line == HEADER_END) {
+            Some(end) => {
+                let header = lines[..end].join("\n");
+                let header: LevelHeader = toml::from_str(&header).map_err(|e| e.to_string())?;
+                (header, &lines[end + 1..])
+            }
+            None => (LevelHeader::default(), &lines[..]),
+        };
+
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        let size = Vector2d::new(width as i32, rows.len() as i32);
+        let mut walls = vec![false; width * rows.len()];
+        let mut starts = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                match c {
+                    '#' => walls[y * width + x] = true,
+                    '.' | ' ' => (),
+                    'S' => starts.push(Vector2d::new(x as i32, y as i32)),
+                    _ => return Err(format!("invalid cell {:?} in row {}", c, y + 1)),
+                }
+            }
+        }
+        // a snake and a fruit need a cell each
+        if walls.iter().filter(|&&wall| !wall).count() < 2 {
+            return Err("level has fewer than two free cells".to_owned());
+        }
+
+        Ok(Self {
+            name: header.name.unwrap_or_else(|| source.to_owned()),
+            author: header.author,
+            source: source.to_owned(),
+            size,
+            walls,
+            starts,
+        })
+    }
+
+    /// A level made of the given walls, indexed by `y * width + x`
+    pub(crate) fn from_walls(
+        name: String,
+        source: String,
+        size: Vector2d,
+        walls: Vec<bool>,
+    ) -> Self {
+        Self {
+            name,
+            author: None,
+            source,
+            size,
+            walls,
+            starts: Vec::new(),
+        }
+    }
+
+    /// Load a level file, named by its path
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text, &path.display().to_string())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The names of the bundled levels
+    pub fn bundled_names() -> impl Iterator<Item = &'static str> {
+        bundled::names(&BUNDLED)
+    }
+
+    /// The bundled level with the given name
+    pub fn bundled(name: &str) -> Option<Self> {
+        bundled::get(&BUNDLED, name, |name, text| Self::parse(text, name))
+    }
+
+    /// The bundled level with the given name, a generated arena like `arena:maze:42:20x20` or
+    /// otherwise the level file at that path
+    pub fn find(name: &str) -> Result<Self, String> {
+        if let Some(arena) = ArenaKind::parse_source(name) {
+            return arena.map_err(|e| format!("level {}: {}", name, e));
+        }
+        bundled::find("level", name, Self::bundled, Self::load)
+    }
+
+    /// The level written in the file format, header included
+    pub fn to_text(&self) -> String {
+        let mut text = format!("name = {:?}\n", self.name);
+        if let Some(author) = &self.author {
+            text += &format!("author = {:?}\n", author);
+        }
+        text += HEADER_END;
+        text.push('\n');
+
+        for y in 0..self.size.y {
+            for x in 0..self.size.x {
+                let pos = Vector2d::new(x, y);
+                text.push(if self.starts.contains(&pos) {
+                    'S'
+                } else if self.is_wall(pos) {
+                    '#'
+                } else {
+                    '.'
+                });
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// The bundled name or path the level was found by
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether this is one of the bundled levels
+    pub fn is_bundled(&self) -> bool {
+        Self::bundled_names().any(|name| name == self.source)
+    }
+
+    /// The number of cells in each direction
+    pub fn size(&self) -> Vector2d {
+        self.size
+    }
+
+    pub fn is_wall(&self, pos: Vector2d) -> bool {
+        pos.is_within(self.size) && self.walls[(pos.y * self.size.x + pos.x) as usize]
+    }
+
+    /// The number of cells that are not walls
+    pub fn free_cells(&self) -> usize {
+        self.walls.iter().filter(|&&wall| !wall).count()
+    }
+
+    /// All wall cells, in reading order
+    pub fn walls(&self) -> impl Iterator<Item = Vector2d> + '_ {
+        let width = self.size.x;
+        (0..self.walls.len())
+            .filter(|&i| self.walls[i])
+            .map(move |i| Vector2d::new(i as i32 % width, i as i32 / width))
+    }
+
+    /// Where the snakes start, in reading order
+    pub fn starts(&self) -> &[Vector2d] {
+        &self.starts
+    }
+}
+
+impl TryFrom<String> for Level {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Self::find(&name)
+    }
+}
+
+impl From<Level> for String {
+    fn from(level: Level) -> Self {
+        level.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameConfig;
+
+    #[test]
+    fn bundled_levels_parse() {
+        for name in Level::bundled_names() {
+            let level = Level::find(name).unwrap();
+            assert_eq!(level.size(), Vector2d::new(20, 20));
+            assert!(level.starts().iter().all(|&start| !level.is_wall(start)));
+            assert!(level.is_bundled());
+        }
+    }
+
+    #[test]
+    fn level_files_round_trip() {
+        let text = "name = \"Tiny\"\nauthor = \"Someone\"\n---\n#...\n.S.\n\n...#\n";
+        let level = Level::parse(text, "tiny.txt").unwrap();
+        assert_eq!(level.name(), "Tiny");
+        assert_eq!(level.author(), Some("Someone"));
+        assert_eq!(level.size(), Vector2d::new(4, 4));
+        assert_eq!(level.starts(), [Vector2d::new(1, 1)]);
+        let walls: Vec<_> = level.walls().collect();
+        assert_eq!(walls, [Vector2d::new(0, 0), Vector2d::new(3, 3)]);
+
+        let parsed = Level::parse(&level.to_text(), "tiny.txt").unwrap();
+        assert_eq!(parsed, level);
+    }
+
+    #[test]
+    fn headers_are_optional_but_cells_are_checked() {
+        let level = Level::parse("S..\n.#.\n", "plain").unwrap();
+        assert_eq!(level.name(), "plain");
+        assert!(Level::parse("S.x\n", "broken").is_err());
+        assert!(Level::parse("nmae = \"typo\"\n---\n...\n", "typo").is_err());
+    }
+
+    #[test]
+    fn levels_leave_room_for_every_snake_and_a_fruit() {
+        assert!(Level::parse("S###\n####\n####\n####\n", "full").is_err());
+        let level = Level::parse("S.##\n####\n####\n####\n", "cramped").unwrap();
+        assert_eq!(level.free_cells(), 2);
+        let mut config = GameConfig {
+            level: Some(level),
+            ..GameConfig::default()
+        };
+        assert_eq!(config.validate(), Ok(()));
+        config.opponents = 1;
+        assert!(config.validate().is_err());
+    }
+}
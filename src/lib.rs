@@ -0,0 +1,75 @@
+//! A headless snake simulation.
+//!
+//! The [`World`] is driven by abstract [`Direction`] commands and reports what happened in each
+//! tick through a [`StepOutcome`], so it can be used without opening a window. Rendering into an
+//! RGBA frame is available through [`World::draw`], in the colors of a [`Theme`] with plain squares
+//! or the sprites of a [`Tileset`], and [`Screenshots`] saves rendered frames as PNG files.
+//!
+//! [`Game`] wraps a world in the title, playing, paused and game over states of a playable game.
+//! Every game is recorded as a [`Replay`], which a [`ReplayPlayer`] can play back deterministically.
+//!
+//! Bots steer a world through the [`SnakeController`] trait, the `snake-sim` binary uses them to
+//! run batches of games without a window. The [`Autopilot`] follows a [`HamiltonianCycle`] and
+//! completes any field that has one.
+
+mod arena;
+mod autopilot;
+mod bundled;
+mod config;
+mod controller;
+mod daily;
+mod date;
+mod difficulty;
+mod font;
+mod fruit;
+mod game;
+mod grid;
+mod highscore;
+mod interval;
+mod level;
+mod paths;
+mod powerup;
+mod render;
+mod replay;
+mod rng;
+mod screenshot;
+mod snake;
+mod theme;
+mod tileset;
+mod vector;
+mod versus;
+mod world;
+
+use image::{ImageBuffer, Rgba};
+
+pub use arena::ArenaKind;
+pub use autopilot::{Autopilot, HamiltonianCycle};
+pub use config::GameConfig;
+pub use controller::{
+    GreedyController, Opponents, PathfindingController, RandomController, ScriptedController,
+    SnakeController, Strategy,
+};
+pub use daily::DailyChallenge;
+pub use date::Date;
+pub use difficulty::{Difficulty, SpeedCurve};
+pub use fruit::{Fruit, FruitEffect, FruitKind};
+pub use game::{Game, GameState};
+pub use highscore::{HighScore, HighScores};
+pub use interval::Interval;
+pub use level::Level;
+pub use paths::{config_dir, data_dir};
+pub use powerup::{ActivePowerUp, Pickup, PowerUp, Stacking};
+pub use replay::{Replay, ReplayPlayer};
+pub use rng::Rng;
+pub use screenshot::{upscale, Screenshots};
+pub use snake::Snake;
+pub use theme::{PowerUpColors, SnakeColors, Theme};
+pub use tileset::{Tile, Tileset};
+pub use vector::{Direction, Vector2d};
+pub use versus::{RoundResult, Scoreboard};
+pub use world::{DeathCause, StepOutcome, WallMode, World};
+
+pub type Frame<'a> = ImageBuffer<Rgba<u8>, &'a mut [u8]>;
+
+/// The height of the HUD strip above the playfield
+pub const HUD_HEIGHT: u32 = 40;
@@ -1,40 +1,319 @@
 use std::{
-    ops::{Add, AddAssign},
-    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    env,
+    path::{Path, PathBuf},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use image::{ImageBuffer, Rgba};
-use imageproc::{drawing, rect::Rect};
+use image::RgbaImage;
 use pixels::{Pixels, SurfaceTexture};
+use snake_pixels::{
+    ArenaKind, DailyChallenge, Difficulty, Direction, Game, GameConfig, GameState, HighScores,
+    Interval, Level, Replay, ReplayPlayer, Rng, Screenshots, StepOutcome, Strategy, Theme, Tileset,
+    WallMode,
+};
 use winit::{
     dpi::LogicalSize,
-    event::{Event, KeyboardInput, VirtualKeyCode, WindowEvent},
+    event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
     window::WindowBuilder,
 };
 
-pub type Frame<'a> = ImageBuffer<Rgba<u8>, &'a mut [u8]>;
+const USAGE: &str = "\
+Usage: snake-pixels [OPTIONS]
+
+Options:
+    --config <FILE>      Read settings from this file instead of the default config.toml
+    --width <CELLS>      Width of the field [default: 20]
+    --height <CELLS>     Height of the field [default: 20]
+    --cell-size <PX>     Width and height of a cell in pixels [default: 40]
+    --walls <MODE>       solid or wrap [default: solid]
+    --level <LEVEL>      pillars, cross, rooms, tunnel or the path of a level file
+    --arena <KIND>       Play a random arena generated from the seed: pillars, rooms or maze
+    --difficulty <NAME>  easy, normal, hard or insane [default: normal]
+    --opponents <N>      Number of computer controlled opponents, 0 to 3 [default: 0]
+    --opponent-strategy <NAME>
+                         random, greedy or pathfinding [default: greedy]
+    --tileset <TILESET>  Draw sprites instead of squares: default or the path of a PNG tileset
+    --theme <THEME>      classic, colorblind, contrast or the path of a theme file
+                         [default: classic]
+    --daily              Play the daily challenge, the same game for everyone on this UTC date
+    --one-attempt        Only the first finished daily challenge enters the high scores
+    --screenshots <DIR>  Save the screenshots taken with F12 here
+                         [default: screenshots in the data directory]
+    --screenshot-scale <N>
+                         Enlarge screenshots by this factor, up to 16 [default: 1]
+    --replay <FILE>      Play back a recorded game
+    --screenshot <TICK>  Save the frame at this tick of the replay without a window and exit
+    --verify <FILE>      Check that a recorded game reaches its score and exit
+    -h, --help           Print this help";
+
+/// The options given on the command line
+#[derive(Debug, Default)]
+struct Options {
+    config: Option<PathBuf>,
+    field_width: Option<u32>,
+    field_height: Option<u32>,
+    cell_size: Option<u32>,
+    walls: Option<WallMode>,
+    level: Option<Level>,
+    arena: Option<ArenaKind>,
+    difficulty: Option<Difficulty>,
+    opponents: Option<u32>,
+    opponent_strategy: Option<Strategy>,
+    tileset: Option<Tileset>,
+    theme: Option<Theme>,
+    daily: bool,
+    one_attempt: bool,
+    screenshots: Option<PathBuf>,
+    screenshot_scale: Option<u32>,
+    replay: Option<PathBuf>,
+    screenshot: Option<usize>,
+    verify: Option<PathBuf>,
+}
 
-const WIDTH: u32 = 800;
-const HEIGHT: u32 = 800;
-const FIELD_SIZE: u32 = 20;
-const SNAKE_SIZE: u32 = WIDTH / FIELD_SIZE;
+/// What is shown in the window
+enum App {
+    Game(Box<Game>),
+    Replay(Box<ReplayPlayer>),
+}
 
-const FPS: u32 = 10;
+fn main() {
+    let options = parse_args().unwrap_or_else(|e| {
+        eprintln!("{}\n\n{}", e, USAGE);
+        process::exit(2);
+    });
 
-const BG_COLOR: Rgba<u8> = Rgba([0, 0, 0, 0xFF]);
-const HEAD_COLOR: Rgba<u8> = Rgba([0, 0xFC, 0, 0xFF]);
-const BODY_COLOR: Rgba<u8> = Rgba([0, 0xFF, 0, 0xFF]);
-const FRUIT_COLOR: Rgba<u8> = Rgba([0xFF, 0, 0, 0xFF]);
+    if let Some(path) = &options.verify {
+        verify(path);
+    }
 
-fn main() {
-    run().unwrap();
+    let config = load_config(&options).unwrap_or_else(|e| {
+        eprintln!("Invalid config: {}", e);
+        process::exit(1);
+    });
+
+    let mut app = match options.replay {
+        Some(path) => {
+            let player = Replay::load(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|replay| ReplayPlayer::new(replay, config.cell_size))
+                .unwrap_or_else(|e| {
+                    eprintln!("Could not load replay {}: {}", path.display(), e);
+                    process::exit(1);
+                });
+            App::Replay(Box::new(player))
+        }
+        None if options.daily => {
+            let mut challenge = DailyChallenge::today();
+            challenge.one_attempt = options.one_attempt;
+            let high_scores = match snake_pixels::data_dir() {
+                Some(dir) => HighScores::open(dir.join(challenge.high_scores_file())),
+                None => HighScores::in_memory(),
+            };
+            App::Game(Box::new(Game::daily(
+                challenge,
+                config.cell_size,
+                high_scores,
+            )))
+        }
+        None => {
+            let high_scores = match snake_pixels::data_dir() {
+                Some(dir) => HighScores::open(dir.join("highscores.toml")),
+                None => HighScores::in_memory(),
+            };
+            App::Game(Box::new(Game::new(config, Rng::new_seeded(), high_scores)))
+        }
+    };
+
+    app.set_tileset(options.tileset);
+    if let Some(theme) = options.theme {
+        app.set_theme(theme);
+    }
+
+    let screenshots = options
+        .screenshots
+        .or_else(|| snake_pixels::data_dir().map(|dir| dir.join("screenshots")))
+        .map(|dir| Screenshots::new(dir, options.screenshot_scale.unwrap_or(1)));
+    if let Some(tick) = options.screenshot {
+        screenshot_replay(app, tick, screenshots.as_ref());
+    }
+
+    run(app, screenshots).unwrap();
+}
+
+/// Play a replay up to a tick without a window, save the frame shown then and exit
+fn screenshot_replay(mut app: App, tick: usize, screenshots: Option<&Screenshots>) -> ! {
+    if let App::Replay(player) = &mut app {
+        while player.tick() < tick && !player.is_finished() {
+            player.step();
+        }
+    }
+
+    let saved = save_screenshot(&app, 1.0, screenshots);
+    process::exit(if saved { 0 } else { 1 });
+}
+
+/// Play a replay back without a window, report whether it reaches its score and exit
+fn verify(path: &Path) -> ! {
+    let result = Replay::load(path)
+        .map_err(|e| e.to_string())
+        .and_then(|replay| replay.verify().map(|()| replay));
+    match result {
+        Ok(replay) => {
+            match replay.daily {
+                Some(date) => println!("Daily challenge of {} verified", date),
+                None => println!("Replay verified"),
+            }
+            println!("Score {} in {} ticks", replay.score, replay.ticks.len());
+            process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Could not verify replay {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "--daily" => {
+                options.daily = true;
+                continue;
+            }
+            "--one-attempt" => {
+                options.one_attempt = true;
+                continue;
+            }
+            _ => (),
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", arg))?;
+        let invalid = |_| format!("Invalid value for {}: {}", arg, value);
+        match arg.as_str() {
+            "--config" => options.config = Some(value.into()),
+            "--width" => options.field_width = Some(value.parse().map_err(invalid)?),
+            "--height" => options.field_height = Some(value.parse().map_err(invalid)?),
+            "--cell-size" => options.cell_size = Some(value.parse().map_err(invalid)?),
+            "--walls" => {
+                let walls = WallMode::from_name(&value)
+                    .ok_or_else(|| format!("Unknown wall mode {}", value))?;
+                options.walls = Some(walls);
+            }
+            "--level" => options.level = Some(Level::find(&value)?),
+            "--arena" => {
+                let arena = ArenaKind::from_name(&value)
+                    .ok_or_else(|| format!("Unknown arena {}", value))?;
+                options.arena = Some(arena);
+            }
+            "--difficulty" => {
+                let difficulty = Difficulty::from_name(&value)
+                    .ok_or_else(|| format!("Unknown difficulty {}", value))?;
+                options.difficulty = Some(difficulty);
+            }
+            "--opponents" => options.opponents = Some(value.parse().map_err(invalid)?),
+            "--opponent-strategy" => {
+                let strategy = Strategy::from_name(&value)
+                    .ok_or_else(|| format!("Unknown strategy {}", value))?;
+                options.opponent_strategy = Some(strategy);
+            }
+            "--tileset" => options.tileset = Some(Tileset::find(&value)?),
+            "--theme" => options.theme = Some(Theme::find(&value)?),
+            "--screenshots" => options.screenshots = Some(value.into()),
+            "--screenshot-scale" => {
+                let scale = value.parse().map_err(invalid)?;
+                if !(1..=Screenshots::MAX_SCALE).contains(&scale) {
+                    return Err(format!(
+                        "{} is not within 1 and {}",
+                        arg,
+                        Screenshots::MAX_SCALE
+                    ));
+                }
+                options.screenshot_scale = Some(scale);
+            }
+            "--replay" => options.replay = Some(value.into()),
+            "--screenshot" => options.screenshot = Some(value.parse().map_err(invalid)?),
+            "--verify" => options.verify = Some(value.into()),
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+
+    if options.level.is_some() && options.arena.is_some() {
+        return Err("--level and --arena can not be combined".to_owned());
+    }
+    if options.one_attempt && !options.daily {
+        return Err("--one-attempt only applies to --daily".to_owned());
+    }
+    if options.screenshot.is_some() && options.replay.is_none() {
+        return Err("--screenshot only applies to --replay".to_owned());
+    }
+
+    Ok(options)
+}
+
+/// Read the config file and apply the options given on the command line on top of it.
+///
+/// Without `--config` the `config.toml` in the config directory is used, if there is one.
+fn load_config(options: &Options) -> Result<GameConfig, String> {
+    let path = options.config.clone().or_else(|| {
+        snake_pixels::config_dir()
+            .map(|dir| dir.join("config.toml"))
+            .filter(|path| path.exists())
+    });
+    let mut config = match path {
+        Some(path) => GameConfig::load(&path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        None => GameConfig::default(),
+    };
+
+    if let Some(width) = options.field_width {
+        config.field_width = width;
+    }
+    if let Some(height) = options.field_height {
+        config.field_height = height;
+    }
+    if let Some(cell_size) = options.cell_size {
+        config.cell_size = cell_size;
+    }
+    if let Some(walls) = options.walls {
+        config.walls = walls;
+    }
+    if let Some(level) = &options.level {
+        config.level = Some(level.clone());
+        config.arena = None;
+    }
+    if let Some(arena) = options.arena {
+        config.arena = Some(arena);
+        config.level = None;
+    }
+    if let Some(difficulty) = options.difficulty {
+        config.difficulty = difficulty;
+        config.speed = None;
+    }
+    if let Some(opponents) = options.opponents {
+        config.opponents = opponents;
+    }
+    if let Some(strategy) = options.opponent_strategy {
+        config.opponent_strategy = strategy;
+    }
+
+    config.validate()?;
+    Ok(config)
 }
 
-fn run() -> Result<(), pixels::Error> {
+fn run(mut app: App, screenshots: Option<Screenshots>) -> Result<(), pixels::Error> {
+    let config = app.config().clone();
     let event_loop = EventLoop::new();
     let window = {
-        let size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
+        let size = LogicalSize::new(config.frame_width() as f64, config.frame_height() as f64);
         WindowBuilder::new()
             .with_title("Snake")
             .with_inner_size(size)
@@ -46,23 +325,22 @@ fn run() -> Result<(), pixels::Error> {
     let mut pixels = {
         let window_size = window.inner_size();
         let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-        Pixels::new(WIDTH, HEIGHT, surface_texture)?
+        Pixels::new(config.frame_width(), config.frame_height(), surface_texture)?
     };
 
-    let rng = Rng::new_seeded();
-    let mut interval = Interval::new(FPS);
-    let mut world = World::new(rng);
+    let mut interval = Interval::new(app.tick_rate());
+    let mut frame_size = (config.frame_width(), config.frame_height());
 
     event_loop.run(move |event, _, control| {
         // Draw current frame
         if let Event::RedrawRequested(_) = event {
-            world.draw(pixels.get_frame());
+            app.draw(pixels.get_frame(), interval.alpha());
             pixels.render().unwrap();
         }
 
         // handle inputs
-        match event {
-            Event::WindowEvent { event, .. } => match event {
+        if let Event::WindowEvent { event, .. } = &event {
+            match event {
                 WindowEvent::CloseRequested
                 | WindowEvent::KeyboardInput {
                     input:
@@ -78,220 +356,251 @@ fn run() -> Result<(), pixels::Error> {
                 WindowEvent::KeyboardInput {
                     input:
                         KeyboardInput {
+                            state: ElementState::Pressed,
                             virtual_keycode: Some(virtual_keycode),
                             ..
                         },
                     ..
                 } => {
-                    world.input(virtual_keycode);
+                    if *virtual_keycode == VirtualKeyCode::F12 {
+                        save_screenshot(&app, interval.alpha(), screenshots.as_ref());
+                        return;
+                    }
+                    app.handle_key(*virtual_keycode);
+                    // a level brings its own field size
+                    let size = (app.config().frame_width(), app.config().frame_height());
+                    if size != frame_size {
+                        frame_size = size;
+                        pixels.resize_buffer(size.0, size.1);
+                        let size = LogicalSize::new(size.0 as f64, size.1 as f64);
+                        window.set_min_inner_size(Some(size));
+                        window.set_inner_size(size);
+                    }
+                    window.request_redraw();
+                }
+                // don't let the snake run into a wall while the player is elsewhere
+                WindowEvent::Focused(false) => {
+                    app.pause();
+                    window.request_redraw();
                 }
                 WindowEvent::Resized(size) => pixels.resize_surface(size.width, size.height),
                 _ => (),
-            },
-            _ => (),
+            }
         }
 
-        if interval.elapsed(control) {
-            if world.update(control) {
+        // run the simulation in fixed steps, however often events arrive
+        if let Event::MainEventsCleared = event {
+            interval.advance();
+            while interval.tick() {
+                if app.update() != StepOutcome::Idle {
+                    window.request_redraw();
+                }
+                // the snake speeds up after eating
+                interval.set_fps(app.tick_rate());
+            }
+
+            // redraw at display rate while the snake slides, sleep until the next tick otherwise
+            if app.is_moving() {
                 window.request_redraw();
+                *control = ControlFlow::Poll;
+            } else {
+                *control = ControlFlow::WaitUntil(interval.deadline());
             }
         }
     });
 }
 
-pub struct World {
-    snake_head: Vector2d,
-    snake_body: Vec<Vector2d>,
-    fruit: Vector2d,
-    dir: Vector2d,
-    rng: Rng,
-}
-
-impl World {
-    pub fn new(rng: Rng) -> Self {
-        let mut me = Self {
-            snake_head: Vector2d::new(FIELD_SIZE as i32 / 2, FIELD_SIZE as i32 / 2),
-            snake_body: Vec::with_capacity(20),
-            fruit: Vector2d::default(),
-            dir: Vector2d::new(0, 0),
-            rng,
-        };
-
-        me.create_fruit();
-        me
-    }
-
-    pub fn input(&mut self, key: VirtualKeyCode) {
-        self.dir = match key {
-            VirtualKeyCode::Up | VirtualKeyCode::W => Vector2d::new(0, -1),
-            VirtualKeyCode::Left | VirtualKeyCode::A => Vector2d::new(-1, 0),
-            VirtualKeyCode::Down | VirtualKeyCode::S => Vector2d::new(0, 1),
-            VirtualKeyCode::Right | VirtualKeyCode::D => Vector2d::new(1, 0),
-            _ => self.dir,
+impl App {
+    /// The config of the world shown, which the window is sized by
+    fn config(&self) -> &GameConfig {
+        match self {
+            App::Game(game) => game.config(),
+            App::Replay(player) => player.world().config(),
         }
     }
 
-    pub fn update(&mut self, flow: &mut ControlFlow) -> bool {
-        if self.dir == Vector2d::new(0, 0) {
-            return false;
+    /// The ticks per second the shown world is updated with
+    fn tick_rate(&self) -> u32 {
+        match self {
+            App::Game(game) => game.world().tick_rate(),
+            App::Replay(player) => player.world().tick_rate(),
         }
+    }
 
-        if !self.snake_body.is_empty() {
-            self.snake_body.rotate_right(1);
-            self.snake_body[0] = self.snake_head;
+    /// Whether the snake is running and has to be redrawn continuously
+    fn is_moving(&self) -> bool {
+        match self {
+            App::Game(game) => {
+                let snakes = game.world().snakes();
+                game.state() == GameState::Playing
+                    && snakes.iter().any(|snake| snake.direction().is_some())
+            }
+            App::Replay(player) => !player.is_paused() && !player.is_finished(),
         }
-        self.snake_head += self.dir;
+    }
 
-        if self.snake_head == self.fruit {
-            let new_body = self.snake_body.last().copied().unwrap_or(self.snake_head);
-            self.snake_body
-                .push(new_body + Vector2d::new(-self.dir.x, -self.dir.y));
-            self.create_fruit();
+    fn set_tileset(&mut self, tileset: Option<Tileset>) {
+        match self {
+            App::Game(game) => game.set_tileset(tileset),
+            App::Replay(player) => player.set_tileset(tileset),
         }
+    }
 
-        if !(0..FIELD_SIZE as i32).contains(&self.snake_head.x)
-            || !(0..FIELD_SIZE as i32).contains(&self.snake_head.y)
-            || self.snake_body.contains(&self.snake_head)
-        {
-            *flow = ControlFlow::Exit;
+    fn set_theme(&mut self, theme: Theme) {
+        match self {
+            App::Game(game) => game.set_theme(theme),
+            App::Replay(player) => player.set_theme(theme),
         }
-
-        true
     }
 
-    pub fn draw(&mut self, frame: &mut [u8]) {
-        let mut frame = Frame::from_raw(WIDTH, HEIGHT, frame).unwrap();
-        // clear background
-        for pixel in frame.pixels_mut() {
-            *pixel = BG_COLOR;
+    fn draw(&self, frame: &mut [u8], alpha: f32) {
+        match self {
+            App::Game(game) => game.draw(frame, alpha),
+            App::Replay(player) => player.draw(frame, alpha),
         }
+    }
 
-        // draw border
-        let border_rect = Rect::at(0, 0).of_size(WIDTH - 1, HEIGHT - 1);
-        drawing::draw_hollow_rect_mut(&mut frame, border_rect, Rgba([0xFF, 0, 0, 0xFF]));
-
-        // draw player
-        let head = self.snake_head;
-        drawing::draw_filled_rect_mut(&mut frame, snake_rect(head.x, head.y), HEAD_COLOR);
-        for body in &self.snake_body {
-            drawing::draw_filled_rect_mut(&mut frame, snake_rect(body.x, body.y), BODY_COLOR)
+    fn render(&self, alpha: f32) -> RgbaImage {
+        match self {
+            App::Game(game) => game.render(alpha),
+            App::Replay(player) => player.render(alpha),
         }
-
-        // draw fruit
-        let fruit = self.fruit;
-        drawing::draw_filled_rect_mut(&mut frame, snake_rect(fruit.x, fruit.y), FRUIT_COLOR);
     }
 
-    fn create_fruit(&mut self) {
-        self.fruit = Vector2d::new(self.random_pos(), self.random_pos());
-        while self.fruit == self.snake_head || self.snake_body.contains(&self.fruit) {
-            self.fruit = Vector2d::new(self.random_pos(), self.random_pos());
+    fn update(&mut self) -> StepOutcome {
+        match self {
+            App::Game(game) => {
+                let outcome = game.update();
+                if outcome.is_over() {
+                    save_game(game);
+                }
+                outcome
+            }
+            App::Replay(player) => player.update(),
         }
     }
 
-    fn random_pos(&mut self) -> i32 {
-        (self.rng.gen() % FIELD_SIZE) as i32
+    fn pause(&mut self) {
+        match self {
+            App::Game(game) => game.pause(),
+            App::Replay(player) => player.pause(),
+        }
     }
-}
-
-fn snake_rect(x: i32, y: i32) -> Rect {
-    Rect::at(x * SNAKE_SIZE as i32, y * SNAKE_SIZE as i32).of_size(SNAKE_SIZE, SNAKE_SIZE)
-}
-
-/// A 2d point or direction
-#[derive(Clone, Copy, Debug, Hash, Default, PartialEq)]
-pub struct Vector2d {
-    pub x: i32,
-    pub y: i32,
-}
 
-impl Vector2d {
-    pub fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
+    /// Apply a pressed key
+    fn handle_key(&mut self, key: VirtualKeyCode) {
+        match self {
+            App::Game(game) => handle_game_key(game, key),
+            App::Replay(player) => handle_replay_key(player, key),
+        }
     }
 }
 
-impl Add for Vector2d {
-    type Output = Self;
-    fn add(self, other: Self) -> Self::Output {
-        Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
+/// Save the frame the app shows and report where, returning whether that worked
+fn save_screenshot(app: &App, alpha: f32, screenshots: Option<&Screenshots>) -> bool {
+    let screenshots = match screenshots {
+        Some(screenshots) => screenshots,
+        None => {
+            eprintln!("Could not save screenshot: no data directory, pass --screenshots");
+            return false;
+        }
+    };
+    match screenshots.save(&app.render(alpha)) {
+        Ok(path) => {
+            println!("Screenshot saved to {}", path.display());
+            true
+        }
+        Err(e) => {
+            eprintln!("Could not save screenshot: {}", e);
+            false
         }
     }
 }
 
-impl AddAssign for Vector2d {
-    fn add_assign(&mut self, other: Self) {
-        *self = *self + other;
+/// Store the high scores and the replay of a finished single player game
+fn save_game(game: &Game) {
+    if game.is_versus() {
+        return;
     }
-}
-
-#[derive(Clone, Debug)]
-pub struct Interval {
-    last: Instant,
-    frame_duration: Duration,
-}
 
-impl Interval {
-    pub fn new(fps: u32) -> Self {
-        Self {
-            last: Instant::now(),
-            frame_duration: Duration::from_secs_f64(1f64 / fps as f64),
-        }
+    if let Err(e) = game.high_scores().save() {
+        eprintln!("Could not save high scores: {}", e);
     }
 
-    // Check if the given frame is already over
-    pub fn elapsed(&mut self, flow: &mut ControlFlow) -> bool {
-        let now = Instant::now();
-        let since_last = now.duration_since(self.last);
-        let el = since_last > self.frame_duration;
-        if el {
-            self.last = Instant::now();
-        } else {
-            *flow = ControlFlow::WaitUntil(now + (self.frame_duration - since_last));
-        }
-
-        el
+    let dir = match snake_pixels::data_dir() {
+        Some(dir) => dir.join("replays"),
+        None => return,
+    };
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let path = dir.join(format!("replay-{}-{}.toml", secs, game.seed()));
+    match game.replay().save(&path) {
+        Ok(()) => println!("Replay saved to {}", path.display()),
+        Err(e) => eprintln!("Could not save replay: {}", e),
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct Rng {
-    last: u32,
-}
-
-impl Rng {
-    const MODULE: u32 = 1 << 31;
-
-    pub fn new(seed: u32) -> Self {
-        Self { last: seed }
-    }
-
-    pub fn new_seeded() -> Self {
-        Self {
-            last: (SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs()
-                .wrapping_add(SNAKE_SIZE as u64 * WIDTH as u64)
-                % Self::MODULE as u64) as u32,
+fn handle_game_key(game: &mut Game, key: VirtualKeyCode) {
+    match (game.state(), key) {
+        (GameState::Title, VirtualKeyCode::Return | VirtualKeyCode::Space) => game.start(),
+        (GameState::Title, VirtualKeyCode::L) => game.cycle_level(),
+        (GameState::Title, VirtualKeyCode::G) => game.cycle_arena(),
+        (GameState::Title, VirtualKeyCode::M) => game.toggle_wall_mode(),
+        (GameState::Title, VirtualKeyCode::Tab) => game.cycle_difficulty(),
+        (GameState::Title, VirtualKeyCode::V) => game.toggle_versus(),
+        (GameState::Title, VirtualKeyCode::O) => game.cycle_opponents(),
+        (GameState::Title, VirtualKeyCode::B) => game.cycle_opponent_strategy(),
+        (GameState::Playing | GameState::Paused, VirtualKeyCode::H) => game.toggle_autopilot(),
+        (GameState::Playing | GameState::Paused, VirtualKeyCode::P | VirtualKeyCode::Space) => {
+            game.toggle_pause()
+        }
+        (GameState::Paused, VirtualKeyCode::N) if cfg!(debug_assertions) => {
+            let outcome = game.step();
+            if outcome.is_over() {
+                save_game(game);
+            }
         }
+        (GameState::GameOver, VirtualKeyCode::Return | VirtualKeyCode::Space) => {
+            game.restart(false)
+        }
+        (GameState::GameOver, VirtualKeyCode::R) => game.restart(true),
+        (_, VirtualKeyCode::T) => game.set_theme(game.theme().next()),
+        (GameState::Playing, key) => {
+            if let Some((player, dir)) = key_direction(key, game.is_versus()) {
+                game.input(player, dir);
+            }
+        }
+        _ => (),
     }
+}
 
-    pub fn gen(&mut self) -> u32 {
-        const MULTIPLIER: u32 = 1103515245;
-        const INCREMENT: u32 = 12345;
-
-        self.last = MULTIPLIER.wrapping_mul(self.last).wrapping_add(INCREMENT) % Self::MODULE;
-        self.last
+fn handle_replay_key(player: &mut ReplayPlayer, key: VirtualKeyCode) {
+    match key {
+        VirtualKeyCode::P | VirtualKeyCode::Space => player.toggle_pause(),
+        VirtualKeyCode::F => player.cycle_speed(),
+        VirtualKeyCode::N | VirtualKeyCode::Period if player.is_paused() => {
+            player.step();
+        }
+        VirtualKeyCode::R => player.restart(),
+        VirtualKeyCode::T => player.set_theme(player.theme().next()),
+        _ => (),
     }
 }
 
-impl Default for Rng {
-    fn default() -> Self {
-        Self {
-            last: 98734677 + SNAKE_SIZE * WIDTH,
-        }
+/// The player and direction a key steers, in versus the arrows belong to the second player
+fn key_direction(key: VirtualKeyCode, versus: bool) -> Option<(usize, Direction)> {
+    let arrows = if versus { 1 } else { 0 };
+    match key {
+        VirtualKeyCode::W => Some((0, Direction::Up)),
+        VirtualKeyCode::A => Some((0, Direction::Left)),
+        VirtualKeyCode::S => Some((0, Direction::Down)),
+        VirtualKeyCode::D => Some((0, Direction::Right)),
+        VirtualKeyCode::Up => Some((arrows, Direction::Up)),
+        VirtualKeyCode::Left => Some((arrows, Direction::Left)),
+        VirtualKeyCode::Down => Some((arrows, Direction::Down)),
+        VirtualKeyCode::Right => Some((arrows, Direction::Right)),
+        _ => None,
     }
 }
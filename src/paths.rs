@@ -0,0 +1,27 @@
+use std::{env, path::PathBuf};
+
+const APP_DIR: &str = "snake-pixels";
+
+/// The directory persistent game data like high scores is stored in.
+///
+/// This is `$XDG_DATA_HOME/snake-pixels`, falling back to `~/.local/share/snake-pixels`.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .map(|dir| dir.join(APP_DIR))
+}
+
+/// The directory the optional `config.toml` is read from.
+///
+/// This is `$XDG_CONFIG_HOME/snake-pixels`, falling back to `~/.config/snake-pixels`.
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .map(|dir| dir.join(APP_DIR))
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Vector2d;
+
+/// A timed effect a snake gets by picking up its pickup
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerUp {
+    /// The snake passes through its own body
+    Ghost,
+    /// The game runs at half its tick rate
+    SlowMotion,
+    /// Fruits close to the head move towards it
+    Magnet,
+    /// The snake survives its next collision, staying in place for a tick
+    Shield,
+    /// Fruits are worth more points
+    Multiplier,
+}
+
+/// What picking up a power-up does while its effect is still running
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Stacking {
+    /// The effect starts over with its full duration
+    Refresh,
+    /// The duration is added to the time left
+    Extend,
+    /// The effect gets stronger up to `max` stacks and starts over with its full duration
+    Stack { max: u32 },
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 5] = [
+        PowerUp::Ghost,
+        PowerUp::SlowMotion,
+        PowerUp::Magnet,
+        PowerUp::Shield,
+        PowerUp::Multiplier,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PowerUp::Ghost => "ghost",
+            PowerUp::SlowMotion => "slowmotion",
+            PowerUp::Magnet => "magnet",
+            PowerUp::Shield => "shield",
+            PowerUp::Multiplier => "multiplier",
+        }
+    }
+
+    /// The power-up with the given [`name`](Self::name)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|power_up| power_up.name() == name)
+    }
+
+    /// The ticks the effect lasts after picking it up
+    pub fn duration(self) -> u32 {
+        match self {
+            PowerUp::Ghost => 40,
+            PowerUp::SlowMotion => 50,
+            PowerUp::Magnet => 60,
+            PowerUp::Shield => 200,
+            PowerUp::Multiplier => 80,
+        }
+    }
+
+    pub fn stacking(self) -> Stacking {
+        match self {
+            PowerUp::Ghost | PowerUp::Magnet => Stacking::Refresh,
+            PowerUp::SlowMotion => Stacking::Extend,
+            // every stack of a shield absorbs one collision, every stack of the multiplier adds
+            // the points of a fruit once more
+            PowerUp::Shield | PowerUp::Multiplier => Stacking::Stack { max: 3 },
+        }
+    }
+}
+
+/// A power-up whose effect is running on a snake
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ActivePowerUp {
+    pub power_up: PowerUp,
+    /// The ticks until the effect ends
+    pub ticks_left: u32,
+    /// How often it was picked up while running, see [`Stacking::Stack`]
+    pub stacks: u32,
+}
+
+impl ActivePowerUp {
+    pub(crate) fn new(power_up: PowerUp) -> Self {
+        Self {
+            power_up,
+            ticks_left: power_up.duration(),
+            stacks: 1,
+        }
+    }
+
+    /// Apply picking up the same power-up again by its [`Stacking`] rule
+    pub(crate) fn pick_up_again(&mut self) {
+        let duration = self.power_up.duration();
+        match self.power_up.stacking() {
+            Stacking::Refresh => self.ticks_left = duration,
+            Stacking::Extend => self.ticks_left += duration,
+            Stacking::Stack { max } => {
+                self.stacks = (self.stacks + 1).min(max);
+                self.ticks_left = duration;
+            }
+        }
+    }
+}
+
+/// A power-up lying on the field
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Pickup {
+    pub pos: Vector2d,
+    pub power_up: PowerUp,
+    /// The tick the pickup disappears at
+    pub expires: u64,
+}
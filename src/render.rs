@@ -0,0 +1,670 @@
+use std::time::Duration;
+
+use image::{Rgba, RgbaImage};
+use imageproc::{drawing, rect::Rect};
+
+use crate::{
+    font, ActivePowerUp, Direction, Frame, Game, GameState, HighScores, PowerUp, ReplayPlayer,
+    RoundResult, Scoreboard, Snake, SnakeColors, Theme, Tile, Tileset, Vector2d, WallMode, World,
+    HUD_HEIGHT,
+};
+
+/// Fruits and power-ups blink for this many ticks before their lifetime is over
+const EXPIRY_BLINK_TICKS: u64 = 16;
+
+const TITLE_SCALE: u32 = 8;
+const TEXT_SCALE: u32 = 4;
+const HUD_SCALE: u32 = 3;
+/// The space kept free left and right of text in pixels
+const TEXT_MARGIN: u32 = 8;
+
+impl Game {
+    /// Draw the HUD, the world and the screen of the current state on top of it.
+    ///
+    /// `alpha` is how far the next tick is, the snake only slides while the game is running.
+    pub fn draw(&self, frame: &mut [u8], alpha: f32) {
+        let alpha = if self.state() == GameState::Playing {
+            alpha
+        } else {
+            1.0
+        };
+        let theme = self.theme();
+        self.world().draw(frame, alpha, theme, self.tileset());
+        let config = self.config();
+        let mut frame =
+            Frame::from_raw(config.frame_width(), config.frame_height(), frame).unwrap();
+        self.draw_hud(&mut frame);
+
+        if self.state() == GameState::Paused {
+            dim(&mut frame);
+        }
+
+        // the title screen needs room for the high score table
+        let y = match self.state() {
+            GameState::Title => HUD_HEIGHT + config.playfield_height() / 20,
+            _ => HUD_HEIGHT + config.playfield_height() / 3,
+        };
+        let mut lines = Lines::new(&mut frame, y, theme);
+
+        match self.state() {
+            GameState::Title => {
+                lines.line("SNAKE", TITLE_SCALE);
+                lines.line("PRESS ENTER TO START", TEXT_SCALE);
+                if let Some(daily) = self.daily_challenge() {
+                    lines.line(&format!("DAILY CHALLENGE {}", daily.date), TEXT_SCALE);
+                    lines.line(&format!("{} ARENA", daily.arena().name()), HUD_SCALE);
+                    if daily.one_attempt {
+                        let attempt = if self.attempt_counts() {
+                            "ONE ATTEMPT COUNTS"
+                        } else {
+                            "ATTEMPT USED, PRACTICE ONLY"
+                        };
+                        lines.line(attempt, HUD_SCALE);
+                    }
+                    lines.line(&format!("T: THEME {}", theme.name), HUD_SCALE);
+                } else {
+                    let level = match &config.level {
+                        Some(level) => level.name(),
+                        None => "OPEN FIELD",
+                    };
+                    let arena = config.arena.map_or("OFF", |arena| arena.name());
+                    // two options to a line leave room for the high score table
+                    lines.line(
+                        &format!("L: LEVEL {}   G: ARENA {}", level, arena),
+                        HUD_SCALE,
+                    );
+                    lines.line(
+                        &format!("M: WALLS {}   T: THEME {}", config.walls.name(), theme.name),
+                        HUD_SCALE,
+                    );
+                    let players = if self.is_versus() {
+                        "2 PLAYERS"
+                    } else {
+                        "1 PLAYER"
+                    };
+                    let difficulty = config.difficulty.name();
+                    lines.line(
+                        &format!("TAB: DIFFICULTY {}   V: {}", difficulty, players),
+                        HUD_SCALE,
+                    );
+                    let strategy = config.opponent_strategy.name();
+                    lines.line(
+                        &format!(
+                            "O: {} OPPONENTS   B: OPPONENTS {}",
+                            config.opponents, strategy
+                        ),
+                        HUD_SCALE,
+                    );
+                }
+                draw_high_scores(&mut lines, self.high_scores());
+            }
+            GameState::Playing => (),
+            GameState::Paused => {
+                lines.line("PAUSED", TITLE_SCALE);
+                lines.line("P / SPACE: RESUME", TEXT_SCALE);
+                if cfg!(debug_assertions) {
+                    lines.line("N: STEP ONE TICK", HUD_SCALE);
+                }
+            }
+            GameState::GameOver if self.is_versus() => {
+                let scoreboard = self.scoreboard();
+                let title = match scoreboard.last_result() {
+                    Some(RoundResult::Won(player)) => format!("PLAYER {} WINS", player + 1),
+                    _ => "DRAW".to_owned(),
+                };
+                lines.line(&title, TITLE_SCALE);
+                lines.line(&format!("ROUNDS {}", format_wins(scoreboard)), TEXT_SCALE);
+                match scoreboard.match_winner() {
+                    Some(player) => {
+                        lines.line(&format!("PLAYER {} WINS THE MATCH", player + 1), TEXT_SCALE);
+                        lines.line("ENTER: NEW MATCH", TEXT_SCALE);
+                    }
+                    None => lines.line("ENTER: NEXT ROUND", TEXT_SCALE),
+                }
+                lines.line("R: RETRY SEED", TEXT_SCALE);
+            }
+            GameState::GameOver => {
+                let title = if self.world().has_won() {
+                    "YOU WIN"
+                } else {
+                    "GAME OVER"
+                };
+                lines.line(title, TITLE_SCALE);
+                lines.line(
+                    &format!("LENGTH {}", self.world().snake(0).length()),
+                    TEXT_SCALE,
+                );
+                if let Some(rank) = self.last_rank() {
+                    lines.line(&format!("NEW HIGH SCORE #{}", rank + 1), TEXT_SCALE);
+                }
+                lines.line("ENTER: NEW GAME", TEXT_SCALE);
+                lines.line("R: RETRY SEED", TEXT_SCALE);
+            }
+        }
+    }
+
+    /// Draw the game into a new image the size of its frame, see [`draw`](Self::draw)
+    pub fn render(&self, alpha: f32) -> RgbaImage {
+        let config = self.config();
+        let mut image = RgbaImage::new(config.frame_width(), config.frame_height());
+        self.draw(&mut image, alpha);
+        image
+    }
+
+    /// Draw score, length, elapsed time and high score into the strip above the playfield, or the
+    /// scores of both players and the match standings in a versus match
+    fn draw_hud(&self, frame: &mut Frame) {
+        let world = self.world();
+        if self.is_versus() {
+            let scoreboard = self.scoreboard();
+            // the round that just ended until the next one starts
+            let round = match self.state() {
+                GameState::GameOver => scoreboard.rounds(),
+                _ => scoreboard.rounds() + 1,
+            };
+            let fields = [
+                format!("P1 {}", world.snake(0).score()),
+                format!("P2 {}", world.snake(1).score()),
+                format!("ROUND {}", round),
+                format!("WINS {}", format_wins(scoreboard)),
+            ];
+            draw_hud(frame, &fields, self.theme());
+            return;
+        }
+
+        let snake = world.snake(0);
+        let mut fields = vec![
+            format!("SCORE {}", snake.score()),
+            format!("LENGTH {}", snake.length()),
+            format!("TIME {}", format_duration(self.elapsed())),
+            if self.is_autopilot() {
+                "AUTOPILOT".to_owned()
+            } else {
+                format!("BEST {}", self.high_score())
+            },
+        ];
+        fields.extend(format_power_ups(world));
+        draw_hud(frame, &fields, self.theme());
+    }
+}
+
+impl ReplayPlayer {
+    /// Draw the world, the playback state and a summary once the replay is over
+    pub fn draw(&self, frame: &mut [u8], alpha: f32) {
+        let alpha = if self.is_paused() { 1.0 } else { alpha };
+        let theme = self.theme();
+        self.world().draw(frame, alpha, theme, self.tileset());
+        let config = self.world().config();
+        let mut frame =
+            Frame::from_raw(config.frame_width(), config.frame_height(), frame).unwrap();
+
+        let replay = self.replay();
+        let state = if self.is_paused() {
+            "PAUSED".to_owned()
+        } else {
+            format!("REPLAY X{}", self.speed())
+        };
+        let mut fields = vec![
+            state,
+            format!("TICK {}/{}", self.tick(), replay.ticks.len()),
+            format!("SCORE {}", self.world().snake(0).score()),
+            format!("LENGTH {}", self.world().snake(0).length()),
+        ];
+        fields.extend(format_power_ups(self.world()));
+        draw_hud(&mut frame, &fields, theme);
+
+        if self.is_finished() {
+            let y = HUD_HEIGHT + config.playfield_height() / 3;
+            let mut lines = Lines::new(&mut frame, y, theme);
+            lines.line("END OF REPLAY", TITLE_SCALE);
+            let verdict = if self.world().snake(0).score() == replay.score {
+                "SCORE VERIFIED"
+            } else {
+                "SCORE MISMATCH"
+            };
+            lines.line(verdict, TEXT_SCALE);
+            lines.line(&format!("RECORDED {}", replay.score), TEXT_SCALE);
+            lines.line("R: RESTART", TEXT_SCALE);
+        }
+    }
+
+    /// Draw the replay into a new image the size of its frame, see [`draw`](Self::draw)
+    pub fn render(&self, alpha: f32) -> RgbaImage {
+        let config = self.world().config();
+        let mut image = RgbaImage::new(config.frame_width(), config.frame_height());
+        self.draw(&mut image, alpha);
+        image
+    }
+}
+
+impl World {
+    /// Draw the world below the HUD strip of a frame sized by its config in the colors of a theme,
+    /// with the sprites of a tileset scaled to the cell size or plain squares.
+    ///
+    /// The snake is drawn `alpha` of the way from where it was before the last tick to where it is
+    /// now, so it slides between cells when drawn more often than it is updated.
+    pub fn draw(&self, frame: &mut [u8], alpha: f32, theme: &Theme, tileset: Option<&Tileset>) {
+        let config = self.config();
+        let cell_size = config.cell_size;
+        let mut frame =
+            Frame::from_raw(config.frame_width(), config.frame_height(), frame).unwrap();
+        // clear background
+        for pixel in frame.pixels_mut() {
+            *pixel = rgba(theme.background);
+        }
+
+        // draw border, dashed if the snake can pass through it
+        let border_rect = Rect::at(0, HUD_HEIGHT as i32)
+            .of_size(config.frame_width() - 1, config.playfield_height() - 1);
+        let border = rgba(theme.border);
+        match config.walls {
+            WallMode::Solid => drawing::draw_hollow_rect_mut(&mut frame, border_rect, border),
+            WallMode::Wrap => draw_dashed_rect(&mut frame, border_rect, cell_size, border),
+        }
+
+        // draw the walls of the level
+        if let Some(level) = &config.level {
+            let color = rgba(theme.walls);
+            for wall in level.walls() {
+                let rect = snake_rect(wall.x, wall.y, cell_size);
+                match tileset.and_then(|tileset| tileset.tile(Tile::Wall)) {
+                    Some(tile) => draw_tile(&mut frame, tile, rect, color),
+                    None => drawing::draw_filled_rect_mut(&mut frame, rect, color),
+                }
+            }
+        }
+
+        // draw snakes
+        for (i, snake) in self.snakes().iter().enumerate() {
+            let colors = theme.snake(i);
+            match tileset {
+                Some(tileset) => {
+                    draw_snake_sprites(&mut frame, snake, colors, alpha, cell_size, tileset)
+                }
+                None => draw_snake(&mut frame, snake, colors, alpha, cell_size),
+            }
+        }
+
+        // draw fruits, blinking shortly before they disappear
+        for fruit in self.fruits() {
+            let left = fruit
+                .expires
+                .map(|expires| expires.saturating_sub(self.ticks()));
+            if matches!(left, Some(left) if left <= EXPIRY_BLINK_TICKS && left % 4 < 2) {
+                continue;
+            }
+            let color = rgba(theme.fruit(self.kind(fruit)));
+            let rect = snake_rect(fruit.pos.x, fruit.pos.y, cell_size);
+            match tileset.and_then(|tileset| tileset.tile(Tile::Fruit)) {
+                Some(tile) => draw_tile(&mut frame, tile, rect, color),
+                None => drawing::draw_filled_rect_mut(&mut frame, rect, color),
+            }
+        }
+
+        // draw power-ups as a dot in a frame
+        for pickup in self.pickups() {
+            let left = pickup.expires.saturating_sub(self.ticks());
+            if left <= EXPIRY_BLINK_TICKS && left % 4 < 2 {
+                continue;
+            }
+            let color = rgba(theme.power_up(pickup.power_up));
+            let rect = snake_rect(pickup.pos.x, pickup.pos.y, cell_size);
+            drawing::draw_hollow_rect_mut(&mut frame, rect, color);
+            let inset = cell_size / 4;
+            let dot = Rect::at(rect.left() + inset as i32, rect.top() + inset as i32)
+                .of_size(cell_size - 2 * inset, cell_size - 2 * inset);
+            drawing::draw_filled_rect_mut(&mut frame, dot, color);
+        }
+    }
+
+    /// Draw the world into a new image the size of its frame, see [`draw`](Self::draw)
+    pub fn render(&self, alpha: f32, theme: &Theme, tileset: Option<&Tileset>) -> RgbaImage {
+        let config = self.config();
+        let mut image = RgbaImage::new(config.frame_width(), config.frame_height());
+        self.draw(&mut image, alpha, theme, tileset);
+        image
+    }
+}
+
+/// A color of a theme, fully opaque
+fn rgba([r, g, b]: [u8; 3]) -> Rgba<u8> {
+    Rgba([r, g, b, 0xFF])
+}
+
+/// Draw a snake whose body cells stay in place while head and tail slide `alpha` of the way
+fn draw_snake(frame: &mut Frame, snake: &Snake, colors: SnakeColors, alpha: f32, cell_size: u32) {
+    let (head_color, body_color) = (rgba(colors.head), rgba(colors.body));
+    let alpha = if snake.is_dead() { 1.0 } else { alpha };
+    let rect = sliding_rect(snake.prev_tail(), snake.tail(), alpha, cell_size);
+    drawing::draw_filled_rect_mut(frame, rect, body_color);
+    for body in snake.body() {
+        let rect = snake_rect(body.x, body.y, cell_size);
+        drawing::draw_filled_rect_mut(frame, rect, body_color)
+    }
+    let rect = sliding_rect(snake.prev_head(), snake.head(), alpha, cell_size);
+    drawing::draw_filled_rect_mut(frame, rect, head_color);
+}
+
+/// Draw a snake with the head, body and tail tiles of a tileset scaled to the cell size, head
+/// and tail sliding like in [`draw_snake`]
+fn draw_snake_sprites(
+    frame: &mut Frame,
+    snake: &Snake,
+    colors: SnakeColors,
+    alpha: f32,
+    cell_size: u32,
+    tileset: &Tileset,
+) {
+    let (head_color, body_color) = (rgba(colors.head), rgba(colors.body));
+    let alpha = if snake.is_dead() { 1.0 } else { alpha };
+    let cells = snake.cells();
+    let mut draw = |tile: Option<Tile>, rect: Rect, color: Rgba<u8>| {
+        if let Some(tile) = tile.and_then(|tile| tileset.tile(tile)) {
+            draw_tile(frame, tile, rect, color);
+        }
+    };
+
+    // every segment between head and tail joins the ones before and after it
+    for i in 1..cells.len().saturating_sub(1) {
+        let tile = direction(cells[i], cells[i - 1])
+            .zip(direction(cells[i], cells[i + 1]))
+            .map(|(front, back)| Tile::Body(front, back));
+        draw(
+            tile,
+            snake_rect(cells[i].x, cells[i].y, cell_size),
+            body_color,
+        );
+    }
+
+    if cells.len() > 1 {
+        let tail = snake.tail();
+        let front = direction(tail, cells[cells.len() - 2]);
+        let prev_tail = snake.prev_tail();
+        if alpha < 1.0 && prev_tail != tail {
+            // the tail slides into its cell, which joins it to the rest of the body meanwhile
+            let back = direction(tail, prev_tail);
+            let rect = snake_rect(tail.x, tail.y, cell_size);
+            draw(
+                front.zip(back).map(|(front, back)| Tile::Body(front, back)),
+                rect,
+                body_color,
+            );
+            let rect = sliding_rect(prev_tail, tail, alpha, cell_size);
+            draw(direction(prev_tail, tail).map(Tile::Tail), rect, body_color);
+        } else {
+            let rect = snake_rect(tail.x, tail.y, cell_size);
+            draw(front.map(Tile::Tail), rect, body_color);
+        }
+    }
+
+    let looking = match cells.get(1) {
+        Some(&neck) => direction(neck, snake.head()),
+        None => Some(snake.direction().unwrap_or(Direction::Up)),
+    };
+    let rect = sliding_rect(snake.prev_head(), snake.head(), alpha, cell_size);
+    draw(looking.map(Tile::Head), rect, head_color);
+}
+
+/// The direction from a cell to a neighbouring one, across the edge of a wrapping field as well
+fn direction(from: Vector2d, to: Vector2d) -> Option<Direction> {
+    match (step(from.x, to.x), step(from.y, to.y)) {
+        (0, -1) => Some(Direction::Up),
+        (0, 1) => Some(Direction::Down),
+        (-1, 0) => Some(Direction::Left),
+        (1, 0) => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+/// Draw a tile into a rectangle of its size, tinted with a color and blended by its alpha
+fn draw_tile(frame: &mut Frame, tile: &RgbaImage, rect: Rect, color: Rgba<u8>) {
+    let (width, height) = frame.dimensions();
+    for (x, y, pixel) in tile.enumerate_pixels() {
+        let (x, y) = (rect.left() + x as i32, rect.top() + y as i32);
+        if x < 0 || y < 0 || x >= width as i32 || y >= height as i32 {
+            continue;
+        }
+
+        let alpha = pixel[3] as u32;
+        let target = frame.get_pixel_mut(x as u32, y as u32);
+        for c in 0..3 {
+            let tinted = pixel[c] as u32 * color[c] as u32 / 0xFF;
+            target[c] = ((tinted * alpha + target[c] as u32 * (0xFF - alpha)) / 0xFF) as u8;
+        }
+    }
+}
+
+/// Draws horizontally centered lines of text below each other
+struct Lines<'f, 'a> {
+    frame: &'f mut Frame<'a>,
+    y: i32,
+    color: Rgba<u8>,
+}
+
+impl<'f, 'a> Lines<'f, 'a> {
+    fn new(frame: &'f mut Frame<'a>, y: u32, theme: &Theme) -> Self {
+        Self {
+            frame,
+            y: y as i32,
+            color: rgba(theme.text),
+        }
+    }
+
+    /// Draw a line at the given scale, or smaller if it would not fit into the frame
+    fn line(&mut self, text: &str, scale: u32) {
+        let scale = self.draw_centered(text, scale);
+        self.y += (font::text_height(scale) * 2) as i32;
+    }
+
+    /// Draw a row of a table, rows sit closer together than lines
+    fn row(&mut self, text: &str, scale: u32) {
+        let scale = self.draw_centered(text, scale);
+        self.y += row_height(scale) as i32;
+    }
+
+    /// The number of table rows at the given scale that still fit above the bottom of the frame
+    fn rows_left(&self, scale: u32) -> usize {
+        let space = self.frame.height() as i32 - self.y - font::text_height(scale) as i32;
+        if space < 0 {
+            return 0;
+        }
+        space as usize / row_height(scale) as usize + 1
+    }
+
+    /// Draw text centered at the current height, returns the scale it was drawn at
+    fn draw_centered(&mut self, text: &str, scale: u32) -> u32 {
+        let width = self.frame.width();
+        let scale = font::fit_scale(text, width.saturating_sub(2 * TEXT_MARGIN), scale);
+        font::draw_text_centered(self.frame, text, width, self.y, scale, self.color);
+        scale
+    }
+}
+
+/// The distance between the rows of a table drawn at the given scale
+fn row_height(scale: u32) -> u32 {
+    font::text_height(scale) * 3 / 2
+}
+
+fn snake_rect(x: i32, y: i32, cell_size: u32) -> Rect {
+    Rect::at(
+        x * cell_size as i32,
+        y * cell_size as i32 + HUD_HEIGHT as i32,
+    )
+    .of_size(cell_size, cell_size)
+}
+
+/// The cell `alpha` of the way from one cell to a neighbouring one.
+///
+/// A move across the edge of a wrapping field slides out of the field instead of across it.
+fn sliding_rect(from: Vector2d, to: Vector2d, alpha: f32, cell_size: u32) -> Rect {
+    let x = to.x as f32 - step(from.x, to.x) as f32 * (1.0 - alpha);
+    let y = to.y as f32 - step(from.y, to.y) as f32 * (1.0 - alpha);
+
+    Rect::at(
+        (x * cell_size as f32).round() as i32,
+        (y * cell_size as f32).round() as i32 + HUD_HEIGHT as i32,
+    )
+    .of_size(cell_size, cell_size)
+}
+
+/// The step along one axis between neighbouring cells, a step across the edge of a wrapping field
+/// goes the short way
+fn step(from: i32, to: i32) -> i32 {
+    match to - from {
+        d if d > 1 => -1,
+        d if d < -1 => 1,
+        d => d,
+    }
+}
+
+/// Draw the outline of a rectangle as dashes, one per cell the outline passes
+fn draw_dashed_rect(frame: &mut Frame, rect: Rect, cell_size: u32, color: Rgba<u8>) {
+    let dash = cell_size / 2;
+    for x in (rect.left()..=rect.right()).step_by(cell_size as usize) {
+        let x = x + (dash / 2) as i32;
+        for y in [rect.top(), rect.bottom()] {
+            drawing::draw_filled_rect_mut(frame, Rect::at(x, y).of_size(dash, 1), color);
+        }
+    }
+    for y in (rect.top()..=rect.bottom()).step_by(cell_size as usize) {
+        let y = y + (dash / 2) as i32;
+        for x in [rect.left(), rect.right()] {
+            drawing::draw_filled_rect_mut(frame, Rect::at(x, y).of_size(1, dash), color);
+        }
+    }
+}
+
+/// Darken the whole frame to put text on top of it
+fn dim(frame: &mut Frame) {
+    for pixel in frame.pixels_mut() {
+        for channel in &mut pixel.0[..3] {
+            *channel /= 3;
+        }
+    }
+}
+
+/// Draw equally spaced text fields into the strip above the playfield
+fn draw_hud(frame: &mut Frame, fields: &[String], theme: &Theme) {
+    let width = frame.width();
+    // a snake that died in the top wall pokes into the strip
+    let strip = Rect::at(0, 0).of_size(width, HUD_HEIGHT);
+    drawing::draw_filled_rect_mut(frame, strip, rgba(theme.background));
+
+    // shrink all fields alike on narrow boards so they stay aligned
+    let column_width = width / fields.len() as u32;
+    let scale = fields
+        .iter()
+        .map(|field| font::fit_scale(field, column_width.saturating_sub(TEXT_MARGIN), HUD_SCALE))
+        .min()
+        .unwrap_or(HUD_SCALE);
+
+    let y = (HUD_HEIGHT - font::text_height(scale)) as i32 / 2;
+    for (i, field) in fields.iter().enumerate() {
+        let x = (i as u32 * column_width + TEXT_MARGIN / 2) as i32;
+        font::draw_text(frame, field, x, y, scale, rgba(theme.hud));
+    }
+}
+
+/// Draw the high score table below the lines drawn so far
+fn draw_high_scores(lines: &mut Lines, high_scores: &HighScores) {
+    lines.line("HIGH SCORES", TEXT_SCALE);
+    if high_scores.entries().is_empty() {
+        lines.line("NO GAMES PLAYED YET", HUD_SCALE);
+    }
+    // a small frame shows as many of the best entries as fit
+    let rows = lines.rows_left(HUD_SCALE);
+    for (i, entry) in high_scores.entries().iter().take(rows).enumerate() {
+        let row = format!(
+            "{:>2}. {:>6} {:>4} {} {}",
+            i + 1,
+            entry.score,
+            entry.length,
+            format_duration(entry.duration()),
+            entry.date
+        );
+        lines.row(&row, HUD_SCALE);
+    }
+}
+
+/// Format the rounds won by each player like `2-1`
+fn format_wins(scoreboard: &Scoreboard) -> String {
+    let wins: Vec<_> = scoreboard
+        .wins()
+        .iter()
+        .map(|wins| wins.to_string())
+        .collect();
+    wins.join("-")
+}
+
+/// The running power-ups of the first player with the seconds they have left, if there are any
+fn format_power_ups(world: &World) -> Option<String> {
+    let power_ups = world.snake(0).power_ups();
+    if power_ups.is_empty() {
+        return None;
+    }
+
+    let rate = world.tick_rate();
+    let field = power_ups
+        .iter()
+        .map(|active| {
+            let secs = active.ticks_left.div_ceil(rate);
+            format!("{} {}", power_up_label(active), secs)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(field)
+}
+
+fn power_up_label(active: &ActivePowerUp) -> String {
+    match active.power_up {
+        PowerUp::Ghost => "GHOST".to_owned(),
+        PowerUp::SlowMotion => "SLOW".to_owned(),
+        PowerUp::Magnet => "MAGNET".to_owned(),
+        PowerUp::Shield if active.stacks > 1 => format!("{} SHIELDS", active.stacks),
+        PowerUp::Shield => "SHIELD".to_owned(),
+        PowerUp::Multiplier => format!("X{}", active.stacks + 1),
+    }
+}
+
+/// Format a duration as minutes and seconds
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Difficulty, GameConfig, HighScore, Rng};
+
+    fn title_screen(entries: usize) -> RgbaImage {
+        let mut high_scores = HighScores::in_memory();
+        for i in 0..entries as u32 {
+            high_scores.insert(HighScore {
+                score: 100_000 - i,
+                length: 400,
+                duration_ms: 3_599_000,
+                seed: i,
+                mode: "solid".to_owned(),
+                difficulty: Difficulty::Normal,
+                date: "2026-10-17".to_owned(),
+            });
+        }
+        Game::new(GameConfig::default(), Rng::new(1), high_scores).render(1.0)
+    }
+
+    #[test]
+    fn the_title_screen_shows_the_whole_high_score_table() {
+        let full = HighScores::MAX_ENTRIES;
+        let (last, before) = (title_screen(full), title_screen(full - 1));
+        // the rows of pixels only the last entry covers
+        let rows: Vec<_> = (0..last.height())
+            .filter(|&y| (0..last.width()).any(|x| last[(x, y)] != before[(x, y)]))
+            .collect();
+        assert_eq!(
+            rows.len() as u32,
+            font::text_height(HUD_SCALE),
+            "the last entry is cut off"
+        );
+    }
+}
@@ -0,0 +1,428 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DailyChallenge, Date, Difficulty, Direction, FruitKind, GameConfig, Level, Opponents, Rng,
+    SpeedCurve, StepOutcome, Strategy, Theme, Tileset, WallMode, World,
+};
+
+/// The version of the replay file format written by this build.
+///
+/// Version 2 places fruits on free cells only and grows the snake at its tail, version 3 picks
+/// those cells and the turns of random opponents from the high bits of the random numbers. Games
+/// recorded with older versions play out differently and can not be verified anymore.
+const FILE_VERSION: u32 = 3;
+
+/// Everything needed to play a game again: its seed and the direction of every tick.
+///
+/// Ticks in which the snake did not move yet are not recorded, they do not change the world.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ReplayFile", into = "ReplayFile")]
+pub struct Replay {
+    pub seed: u32,
+    pub field_width: u32,
+    pub field_height: u32,
+    pub level: Option<Level>,
+    pub walls: WallMode,
+    pub difficulty: Difficulty,
+    pub speed: Option<SpeedCurve>,
+    pub opponents: u32,
+    pub opponent_strategy: Strategy,
+    /// The date of the daily challenge the game was played as, if it was
+    pub daily: Option<Date>,
+    pub fruits: Vec<FruitKind>,
+    pub power_ups: bool,
+    /// The score the recorded game ended with
+    pub score: u32,
+    pub ticks: Vec<Direction>,
+}
+
+/// The on-disk representation of a [`Replay`], the ticks are stored as a string of `UDLR`
+#[derive(Serialize, Deserialize)]
+struct ReplayFile {
+    version: u32,
+    seed: u32,
+    field_width: u32,
+    field_height: u32,
+    /// The whole level in the level file format, so the replay does not depend on any other file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    level: Option<String>,
+    walls: WallMode,
+    difficulty: Difficulty,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    speed: Option<SpeedCurve>,
+    opponents: u32,
+    opponent_strategy: Strategy,
+    /// The date of the daily challenge formatted as `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    daily: Option<String>,
+    score: u32,
+    ticks: String,
+    fruits: Vec<FruitKind>,
+    power_ups: bool,
+}
+
+impl Replay {
+    /// An empty recording of a game played with the given config
+    pub fn new(seed: u32, config: &GameConfig) -> Self {
+        Self {
+            seed,
+            field_width: config.field_width,
+            field_height: config.field_height,
+            level: config.level.clone(),
+            walls: config.walls,
+            difficulty: config.difficulty,
+            speed: config.speed,
+            opponents: config.opponents,
+            opponent_strategy: config.opponent_strategy,
+            daily: None,
+            fruits: config.fruits.clone(),
+            power_ups: config.power_ups,
+            score: 0,
+            ticks: Vec::new(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        toml::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let text = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, text)
+    }
+
+    /// The config to play the replay with, drawn with the given cell size
+    pub fn config(&self, cell_size: u32) -> GameConfig {
+        GameConfig {
+            field_width: self.field_width,
+            field_height: self.field_height,
+            level: self.level.clone(),
+            // a replay stores the arena it was played in as its level
+            arena: None,
+            cell_size,
+            walls: self.walls,
+            players: 1,
+            opponents: self.opponents,
+            opponent_strategy: self.opponent_strategy,
+            difficulty: self.difficulty,
+            speed: self.speed,
+            fruits: self.fruits.clone(),
+            power_ups: self.power_ups,
+        }
+    }
+
+    /// Play the replay back and check that it reaches the score it claims, and that a daily
+    /// challenge was played by the rules of its day
+    pub fn verify(&self) -> Result<(), String> {
+        if let Some(date) = self.daily {
+            DailyChallenge::new(date).check_rules(self)?;
+        }
+
+        let mut player = ReplayPlayer::new(self.clone(), GameConfig::default().cell_size)?;
+        while !player.is_finished() {
+            player.step();
+        }
+        let score = player.world().snake(0).score();
+        if score != self.score {
+            return Err(format!(
+                "the replay reaches {} points, not {}",
+                score, self.score
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Record the direction the snake moved in during the next tick
+    pub fn record(&mut self, dir: Direction) {
+        self.ticks.push(dir);
+    }
+}
+
+impl TryFrom<ReplayFile> for Replay {
+    type Error = String;
+
+    fn try_from(file: ReplayFile) -> Result<Self, Self::Error> {
+        if file.version != FILE_VERSION {
+            return Err(format!("unsupported replay version {}", file.version));
+        }
+
+        let ticks = file
+            .ticks
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                Direction::from_char(c)
+                    .ok_or_else(|| format!("invalid direction {:?} in replay", c))
+            })
+            .collect::<Result<_, _>>()?;
+        let level = match &file.level {
+            Some(text) => Some(Level::parse(text, "replay")?),
+            None => None,
+        };
+        let daily = match &file.daily {
+            Some(date) => Some(date.parse()?),
+            None => None,
+        };
+
+        Ok(Self {
+            seed: file.seed,
+            field_width: file.field_width,
+            field_height: file.field_height,
+            level,
+            walls: file.walls,
+            difficulty: file.difficulty,
+            speed: file.speed,
+            opponents: file.opponents,
+            opponent_strategy: file.opponent_strategy,
+            daily,
+            fruits: file.fruits,
+            power_ups: file.power_ups,
+            score: file.score,
+            ticks,
+        })
+    }
+}
+
+impl From<Replay> for ReplayFile {
+    fn from(replay: Replay) -> Self {
+        let ticks = replay.ticks.iter().map(|dir| dir.to_char()).collect();
+
+        Self {
+            version: FILE_VERSION,
+            seed: replay.seed,
+            field_width: replay.field_width,
+            field_height: replay.field_height,
+            level: replay.level.as_ref().map(Level::to_text),
+            walls: replay.walls,
+            difficulty: replay.difficulty,
+            speed: replay.speed,
+            opponents: replay.opponents,
+            opponent_strategy: replay.opponent_strategy,
+            daily: replay.daily.map(|date| date.to_string()),
+            score: replay.score,
+            ticks,
+            fruits: replay.fruits,
+            power_ups: replay.power_ups,
+        }
+    }
+}
+
+/// Drives a [`World`] from a [`Replay`] with pause, fast-forward and single-step controls
+#[derive(Debug)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    world: World,
+    /// Steer the opponents exactly like in the recorded game, they are seeded like the world
+    opponents: Opponents,
+    tick: usize,
+    paused: bool,
+    /// How many ticks are played per frame
+    speed: u32,
+    /// The sprites the world is drawn with, scaled to the cell size, plain squares without
+    tileset: Option<Tileset>,
+    /// The colors everything is drawn in
+    theme: Theme,
+}
+
+impl ReplayPlayer {
+    const MAX_SPEED: u32 = 8;
+
+    /// Create a player for the replay, fails if it was recorded with an invalid config
+    pub fn new(replay: Replay, cell_size: u32) -> Result<Self, String> {
+        let config = replay.config(cell_size);
+        config.validate()?;
+
+        Ok(Self {
+            world: World::new(&config, Rng::new(replay.seed)),
+            opponents: Opponents::new(&config, replay.seed),
+            replay,
+            tick: 0,
+            paused: false,
+            speed: 1,
+            tileset: None,
+            theme: Theme::default(),
+        })
+    }
+
+    /// Play the ticks of one frame, unless paused
+    pub fn update(&mut self) -> StepOutcome {
+        if self.paused {
+            return StepOutcome::Idle;
+        }
+
+        let mut outcome = StepOutcome::Idle;
+        for _ in 0..self.speed {
+            outcome = self.step();
+        }
+
+        outcome
+    }
+
+    /// Play exactly one tick
+    pub fn step(&mut self) -> StepOutcome {
+        if self.is_finished() {
+            return StepOutcome::Idle;
+        }
+
+        self.world.input(0, self.replay.ticks[self.tick]);
+        self.opponents.steer(&mut self.world);
+        self.tick += 1;
+        self.world.update()
+    }
+
+    /// Draw the world with the sprites of a tileset instead of plain squares
+    pub fn set_tileset(&mut self, tileset: Option<Tileset>) {
+        let cell_size = self.world.config().cell_size;
+        self.tileset = tileset.map(|tileset| tileset.scaled(cell_size));
+    }
+
+    pub fn tileset(&self) -> Option<&Tileset> {
+        self.tileset.as_ref()
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Start playing from the first tick again
+    pub fn restart(&mut self) {
+        let config = self.world.config().clone();
+        self.world = World::new(&config, Rng::new(self.replay.seed));
+        self.opponents = Opponents::new(&config, self.replay.seed);
+        self.tick = 0;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Double the playback speed, wrapping back to normal speed after the maximum
+    pub fn cycle_speed(&mut self) {
+        self.speed = if self.speed >= Self::MAX_SPEED {
+            1
+        } else {
+            self.speed * 2
+        };
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.tick >= self.replay.ticks.len() || self.world.is_over()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn speed(&self) -> u32 {
+        self.speed
+    }
+
+    /// The number of ticks played so far
+    pub fn tick(&self) -> usize {
+        self.tick
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+    use crate::{Game, HighScores};
+
+    /// 300 ticks of the autopilot on the pillars level next to an opponent
+    fn recorded_game() -> Replay {
+        let config = GameConfig {
+            level: Some(Level::find("pillars").unwrap()),
+            opponents: 1,
+            ..GameConfig::default()
+        };
+        let mut game = Game::new(config, Rng::new(7), HighScores::in_memory());
+        game.start();
+        game.toggle_autopilot();
+        for _ in 0..300 {
+            game.update();
+        }
+
+        let mut replay = game.replay().clone();
+        replay.score = game.world().snake(0).score();
+        replay
+    }
+
+    #[test]
+    fn replays_round_trip_and_play_back_the_same_game() {
+        let replay = recorded_game();
+        assert!(replay.score > 0);
+        assert_eq!(replay.verify(), Ok(()));
+
+        let path = env::temp_dir().join(format!("snake-pixels-{}-replay.toml", process::id()));
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        // the level is stored as its text, not by the name it was found with
+        let without_level = |replay: &Replay| Replay {
+            level: None,
+            ..replay.clone()
+        };
+        assert_eq!(without_level(&loaded), without_level(&replay));
+        let walls: Vec<_> = loaded.level.as_ref().unwrap().walls().collect();
+        assert_eq!(
+            walls,
+            replay.level.as_ref().unwrap().walls().collect::<Vec<_>>()
+        );
+        assert_eq!(loaded.verify(), Ok(()));
+
+        let mut cheated = loaded;
+        cheated.score += 10;
+        assert!(cheated.verify().is_err());
+    }
+
+    #[test]
+    fn replay_files_are_checked() {
+        let text = toml::to_string(&recorded_game()).unwrap();
+        assert!(toml::from_str::<Replay>(&text).is_ok());
+
+        let version = format!("version = {}", FILE_VERSION);
+        let old = text.replace(&version, "version = 1");
+        assert_ne!(old, text);
+        assert!(toml::from_str::<Replay>(&old).is_err());
+
+        let missing: String = text
+            .lines()
+            .filter(|line| !line.starts_with("power_ups"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        assert_ne!(missing, text);
+        assert!(toml::from_str::<Replay>(&missing).is_err());
+
+        let ticks = text.find("ticks = \"").unwrap() + "ticks = \"".len();
+        let mut invalid = text.clone();
+        invalid.insert(ticks, 'X');
+        assert!(toml::from_str::<Replay>(&invalid).is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    last: u32,
+}
+
+impl Rng {
+    const MODULE: u32 = 1 << 31;
+    /// Mixed into seeds derived from the clock
+    const SEED_OFFSET: u32 = 32000;
+
+    pub fn new(seed: u32) -> Self {
+        Self { last: seed }
+    }
+
+    pub fn new_seeded() -> Self {
+        Self {
+            last: (SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                .wrapping_add(Self::SEED_OFFSET as u64)
+                % Self::MODULE as u64) as u32,
+        }
+    }
+
+    pub fn gen(&mut self) -> u32 {
+        const MULTIPLIER: u32 = 1103515245;
+        const INCREMENT: u32 = 12345;
+
+        self.last = MULTIPLIER.wrapping_mul(self.last).wrapping_add(INCREMENT) % Self::MODULE;
+        self.last
+    }
+
+    /// A number below `bound`, taken from the high bits which are more random than the low ones
+    pub fn gen_below(&mut self, bound: u32) -> u32 {
+        ((self.gen() as u64 * bound as u64) >> 31) as u32
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self {
+            last: 98734677 + Self::SEED_OFFSET,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use image::{imageops, imageops::FilterType, RgbaImage};
+
+use crate::Date;
+
+/// Saves rendered frames as PNG files in a directory, optionally scaled up
+#[derive(Clone, Debug)]
+pub struct Screenshots {
+    dir: PathBuf,
+    /// The factor frames are enlarged by, 1 to keep their size
+    scale: u32,
+}
+
+impl Screenshots {
+    /// The largest factor frames are enlarged by, larger ones are capped
+    pub const MAX_SCALE: u32 = 16;
+
+    pub fn new(dir: impl Into<PathBuf>, scale: u32) -> Self {
+        Self {
+            dir: dir.into(),
+            scale: scale.clamp(1, Self::MAX_SCALE),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Save a frame named by the current time and return its path
+    pub fn save(&self, frame: &RgbaImage) -> io::Result<PathBuf> {
+        let name = file_name(SystemTime::now());
+        // two screenshots taken within a millisecond
+        let mut path = self.dir.join(format!("{}.png", name));
+        let mut copy = 1;
+        while path.exists() {
+            path = self.dir.join(format!("{}-{}.png", name, copy));
+            copy += 1;
+        }
+
+        self.save_as(frame, &path)?;
+        Ok(path)
+    }
+
+    /// Save a frame under the given path, relative to the directory, creating the directory if it
+    /// is missing
+    pub fn save_as(&self, frame: &RgbaImage, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = self.dir.join(path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        upscale(frame, self.scale)
+            .save(&path)
+            .map_err(io::Error::other)
+    }
+}
+
+/// A frame enlarged by an integer factor, every pixel becoming a sharp square
+pub fn upscale(frame: &RgbaImage, scale: u32) -> RgbaImage {
+    if scale <= 1 {
+        return frame.clone();
+    }
+
+    let (width, height) = frame.dimensions();
+    imageops::resize(frame, width * scale, height * scale, FilterType::Nearest)
+}
+
+/// The name of a screenshot taken at a point in time, in UTC down to the millisecond
+fn file_name(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap();
+    let secs = since_epoch.as_secs();
+    let date = Date::from_unix_days((secs / (24 * 60 * 60)) as i64);
+    let secs_of_day = secs % (24 * 60 * 60);
+    format!(
+        "screenshot-{}-{:02}-{:02}-{:02}-{:03}",
+        date,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use image::Rgba;
+
+    use super::*;
+
+    #[test]
+    fn screenshots_are_named_by_utc_time() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_792_197_005_042);
+        assert_eq!(file_name(time), "screenshot-2026-10-17-00-30-05-042");
+    }
+
+    #[test]
+    fn upscaling_keeps_pixels_sharp() {
+        let mut frame = RgbaImage::new(2, 1);
+        frame.put_pixel(1, 0, Rgba([0xFF, 0, 0, 0xFF]));
+        let scaled = upscale(&frame, 3);
+        assert_eq!(scaled.dimensions(), (6, 3));
+        assert_eq!(scaled.get_pixel(2, 2), &Rgba([0, 0, 0, 0]));
+        assert_eq!(scaled.get_pixel(3, 0), &Rgba([0xFF, 0, 0, 0xFF]));
+        assert_eq!(upscale(&frame, 1), frame);
+    }
+}
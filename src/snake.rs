@@ -0,0 +1,334 @@
+use std::collections::VecDeque;
+
+use crate::{ActivePowerUp, DeathCause, Direction, PowerUp, Vector2d};
+
+/// How many turns can be buffered ahead of the ticks consuming them
+const INPUT_QUEUE_LEN: usize = 3;
+
+/// One of the snakes in a [`World`](crate::World)
+#[derive(Clone, Debug)]
+pub struct Snake {
+    /// The cells of the snake, head at the front and tail at the back
+    cells: VecDeque<Vector2d>,
+    /// Where the head and the last segment were before the last tick, to draw the move between
+    prev_head: Vector2d,
+    prev_tail: Vector2d,
+    score: u32,
+    /// The cells the snake still grows by, one per tick
+    growth: u32,
+    dir: Option<Direction>,
+    /// Turns that were input but not yet applied, one is consumed per tick
+    inputs: VecDeque<Direction>,
+    death: Option<DeathCause>,
+    /// The effects of the power-ups picked up, at most one per power-up
+    power_ups: Vec<ActivePowerUp>,
+}
+
+impl Snake {
+    /// A snake of length one that waits for its first turn
+    pub(crate) fn new(head: Vector2d) -> Self {
+        Self {
+            cells: VecDeque::from([head]),
+            prev_head: head,
+            prev_tail: head,
+            score: 0,
+            growth: 0,
+            dir: None,
+            inputs: VecDeque::with_capacity(INPUT_QUEUE_LEN),
+            death: None,
+            power_ups: Vec::new(),
+        }
+    }
+
+    /// Queue a turn for one of the next ticks.
+    ///
+    /// Turns that repeat or reverse the direction the snake will be heading in at that point are
+    /// dropped, as are turns exceeding the queue length.
+    pub(crate) fn input(&mut self, dir: Direction) {
+        if let Some(last) = self.inputs.back().copied().or(self.dir) {
+            if dir == last || dir == last.opposite() {
+                return;
+            }
+        }
+
+        if self.inputs.len() < INPUT_QUEUE_LEN {
+            self.inputs.push_back(dir);
+        }
+    }
+
+    /// Apply the next queued turn at the start of a tick, returns the direction to move in
+    pub(crate) fn turn(&mut self) -> Option<Direction> {
+        if let Some(dir) = self.inputs.pop_front() {
+            self.dir = Some(dir);
+        }
+
+        self.prev_head = self.head();
+        self.prev_tail = self.tail();
+        self.dir
+    }
+
+    /// Move the head to `head`, returns the cell the tail left unless the snake grows
+    pub(crate) fn advance(&mut self, head: Vector2d) -> Option<Vector2d> {
+        let tail = if self.growth > 0 {
+            self.growth -= 1;
+            None
+        } else {
+            self.cells.pop_back()
+        };
+        self.cells.push_front(head);
+        tail
+    }
+
+    /// Grow by the given number of cells, starting with the next move
+    pub(crate) fn grow(&mut self, cells: u32) {
+        self.growth += cells;
+    }
+
+    /// Take back growth that has not happened yet
+    pub(crate) fn cancel_growth(&mut self, cells: u32) {
+        self.growth = self.growth.saturating_sub(cells);
+    }
+
+    /// Drop up to `cells` segments at the tail, keeping the head, returns the cells they left
+    pub(crate) fn shrink(&mut self, cells: u32) -> Vec<Vector2d> {
+        let count = (cells as usize).min(self.cells.len() - 1);
+        let left = self.cells.split_off(self.cells.len() - count);
+        // the new tail does not slide after the old one
+        self.prev_tail = self.tail();
+        left.into()
+    }
+
+    /// Undo the last move after a collision the snake survived, leaving it one tick to turn away.
+    ///
+    /// `tail` is what [`advance`](Self::advance) returned for the move. The snake loses that cell
+    /// unless `keep_tail`, when another head took it in the meantime.
+    pub(crate) fn retreat(&mut self, tail: Option<Vector2d>, keep_tail: bool) {
+        self.cells.pop_front();
+        match tail {
+            Some(tail) if keep_tail || self.cells.is_empty() => self.cells.push_back(tail),
+            Some(_) => (),
+            None => self.growth += 1,
+        }
+        self.prev_head = self.head();
+        self.prev_tail = self.tail();
+    }
+
+    /// Start the effect of a power-up, or apply its [`Stacking`](crate::Stacking) rule if it is
+    /// already running
+    pub(crate) fn pick_up(&mut self, power_up: PowerUp) {
+        match self
+            .power_ups
+            .iter_mut()
+            .find(|active| active.power_up == power_up)
+        {
+            Some(active) => active.pick_up_again(),
+            None => self.power_ups.push(ActivePowerUp::new(power_up)),
+        }
+    }
+
+    /// Count down the effects of the power-ups by one tick, dropping those that ended
+    pub(crate) fn tick_power_ups(&mut self) {
+        for active in &mut self.power_ups {
+            active.ticks_left = active.ticks_left.saturating_sub(1);
+        }
+        self.power_ups.retain(|active| active.ticks_left > 0);
+    }
+
+    /// Use up one stack of the shield, returns whether there was one
+    pub(crate) fn use_shield(&mut self) -> bool {
+        let index = match self
+            .power_ups
+            .iter()
+            .position(|active| active.power_up == PowerUp::Shield)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let shield = &mut self.power_ups[index];
+        shield.stacks -= 1;
+        if shield.stacks == 0 {
+            self.power_ups.remove(index);
+        }
+        true
+    }
+
+    pub(crate) fn kill(&mut self, cause: DeathCause) {
+        self.death = Some(cause);
+    }
+
+    pub(crate) fn add_score(&mut self, points: u32) {
+        self.score += points;
+    }
+
+    pub fn head(&self) -> Vector2d {
+        self.cells[0]
+    }
+
+    /// The segments behind the head, ending with the tail
+    pub fn body(&self) -> impl Iterator<Item = &Vector2d> + '_ {
+        self.cells.iter().skip(1)
+    }
+
+    /// All segments of the snake, head first
+    pub fn cells(&self) -> &VecDeque<Vector2d> {
+        &self.cells
+    }
+
+    /// The last segment of the snake, the head if it has no body
+    pub fn tail(&self) -> Vector2d {
+        self.cells[self.cells.len() - 1]
+    }
+
+    /// Where the head was before the last tick
+    pub fn prev_head(&self) -> Vector2d {
+        self.prev_head
+    }
+
+    /// Where the last segment was before the last tick
+    pub fn prev_tail(&self) -> Vector2d {
+        self.prev_tail
+    }
+
+    /// The length of the snake including its head
+    pub fn length(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether the tail stays in place with the next move
+    pub fn is_growing(&self) -> bool {
+        self.growth > 0
+    }
+
+    /// The number of moves the tail still stays in place for
+    pub fn growth(&self) -> u32 {
+        self.growth
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn direction(&self) -> Option<Direction> {
+        self.dir
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.death.is_some()
+    }
+
+    pub fn death_cause(&self) -> Option<DeathCause> {
+        self.death
+    }
+
+    /// The effects of the power-ups the snake picked up that are still running
+    pub fn power_ups(&self) -> &[ActivePowerUp] {
+        &self.power_ups
+    }
+
+    /// The running effect of a power-up
+    pub fn power_up(&self, power_up: PowerUp) -> Option<&ActivePowerUp> {
+        self.power_ups
+            .iter()
+            .find(|active| active.power_up == power_up)
+    }
+
+    /// The factor the points of fruits are multiplied with
+    pub fn score_multiplier(&self) -> u32 {
+        self.power_up(PowerUp::Multiplier)
+            .map_or(1, |active| active.stacks + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turns(snake: &mut Snake, ticks: usize) -> Vec<Option<Direction>> {
+        (0..ticks).map(|_| snake.turn()).collect()
+    }
+
+    #[test]
+    fn turns_are_applied_one_per_tick() {
+        let mut snake = Snake::new(Vector2d::new(5, 5));
+        assert_eq!(snake.turn(), None);
+        snake.input(Direction::Right);
+        snake.input(Direction::Up);
+        snake.input(Direction::Left);
+        assert_eq!(
+            turns(&mut snake, 4),
+            [
+                Direction::Right,
+                Direction::Up,
+                Direction::Left,
+                Direction::Left
+            ]
+            .map(Some)
+        );
+    }
+
+    #[test]
+    fn repeated_and_reversing_turns_are_dropped() {
+        let mut snake = Snake::new(Vector2d::new(5, 5));
+        snake.input(Direction::Right);
+        snake.input(Direction::Right);
+        // reverses the queued turn, not the current direction
+        snake.input(Direction::Left);
+        assert_eq!(turns(&mut snake, 2), [Some(Direction::Right); 2]);
+
+        snake.input(Direction::Left);
+        snake.input(Direction::Up);
+        snake.input(Direction::Down);
+        assert_eq!(turns(&mut snake, 2), [Some(Direction::Up); 2]);
+    }
+
+    #[test]
+    fn the_queue_holds_three_turns() {
+        let mut snake = Snake::new(Vector2d::new(5, 5));
+        snake.input(Direction::Right);
+        snake.turn();
+        for dir in [
+            Direction::Up,
+            Direction::Left,
+            Direction::Down,
+            Direction::Right,
+        ] {
+            snake.input(dir);
+        }
+        assert_eq!(
+            turns(&mut snake, 4),
+            [
+                Direction::Up,
+                Direction::Left,
+                Direction::Down,
+                Direction::Down
+            ]
+            .map(Some)
+        );
+    }
+
+    #[test]
+    fn power_ups_stack_or_refresh_by_rule() {
+        let mut snake = Snake::new(Vector2d::new(0, 0));
+        snake.pick_up(PowerUp::Ghost);
+        snake.pick_up(PowerUp::SlowMotion);
+        snake.pick_up(PowerUp::Multiplier);
+        snake.tick_power_ups();
+        snake.pick_up(PowerUp::Ghost);
+        snake.pick_up(PowerUp::SlowMotion);
+        snake.pick_up(PowerUp::Multiplier);
+        let ticks_left = |power_up| snake.power_up(power_up).unwrap().ticks_left;
+        assert_eq!(ticks_left(PowerUp::Ghost), PowerUp::Ghost.duration());
+        assert_eq!(
+            ticks_left(PowerUp::SlowMotion),
+            2 * PowerUp::SlowMotion.duration() - 1
+        );
+        assert_eq!(snake.score_multiplier(), 3);
+
+        for _ in 0..PowerUp::Ghost.duration() {
+            snake.tick_power_ups();
+        }
+        assert!(snake.power_up(PowerUp::Ghost).is_none());
+        assert!(snake.power_up(PowerUp::SlowMotion).is_some());
+    }
+}
@@ -0,0 +1,176 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{bundled, FruitKind, PowerUp};
+
+/// The themes that come with the game, by name
+const BUNDLED: [(&str, &str); 3] = [
+    ("classic", include_str!("../themes/classic.toml")),
+    ("colorblind", include_str!("../themes/colorblind.toml")),
+    ("contrast", include_str!("../themes/contrast.toml")),
+];
+
+/// The colors everything is drawn in, read from a TOML file.
+///
+/// All colors are given as RGB.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Theme {
+    pub name: String,
+    pub background: [u8; 3],
+    /// The edge of the field
+    pub border: [u8; 3],
+    /// The walls of levels and arenas
+    pub walls: [u8; 3],
+    /// The text of the title, pause and game over screens
+    pub text: [u8; 3],
+    pub hud: [u8; 3],
+    /// The colors of each snake, the players' first and the opponents' after them. Further snakes
+    /// start over with the first colors.
+    pub snakes: Vec<SnakeColors>,
+    /// Colors replacing those of the fruit table, by the name of the fruit
+    #[serde(default)]
+    pub fruits: BTreeMap<String, [u8; 3]>,
+    pub power_ups: PowerUpColors,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SnakeColors {
+    pub head: [u8; 3],
+    pub body: [u8; 3],
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PowerUpColors {
+    pub ghost: [u8; 3],
+    pub slowmotion: [u8; 3],
+    pub magnet: [u8; 3],
+    pub shield: [u8; 3],
+    pub multiplier: [u8; 3],
+}
+
+impl Theme {
+    /// The names of the bundled themes, the default one first
+    pub fn bundled_names() -> impl Iterator<Item = &'static str> {
+        bundled::names(&BUNDLED)
+    }
+
+    /// The bundled theme with the given name
+    pub fn bundled(name: &str) -> Option<Self> {
+        bundled::get(&BUNDLED, name, |_, text| Self::parse(text))
+    }
+
+    /// Load a theme file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The bundled theme with the given name, otherwise the theme file at that path
+    pub fn find(name: &str) -> Result<Self, String> {
+        bundled::find("theme", name, Self::bundled, Self::load)
+    }
+
+    /// Read a theme from the text of a theme file
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let theme: Self = toml::from_str(text).map_err(|e| e.to_string())?;
+        if theme.snakes.is_empty() {
+            return Err("the theme has no snake colors".to_owned());
+        }
+
+        Ok(theme)
+    }
+
+    /// The colors of the snake with the given index
+    pub fn snake(&self, index: usize) -> SnakeColors {
+        self.snakes[index % self.snakes.len()]
+    }
+
+    /// The color of a kind of fruit, its own one unless the theme replaces it
+    pub fn fruit(&self, kind: &FruitKind) -> [u8; 3] {
+        self.fruits.get(&kind.name).copied().unwrap_or(kind.color)
+    }
+
+    pub fn power_up(&self, power_up: PowerUp) -> [u8; 3] {
+        let colors = &self.power_ups;
+        match power_up {
+            PowerUp::Ghost => colors.ghost,
+            PowerUp::SlowMotion => colors.slowmotion,
+            PowerUp::Magnet => colors.magnet,
+            PowerUp::Shield => colors.shield,
+            PowerUp::Multiplier => colors.multiplier,
+        }
+    }
+
+    /// The bundled theme following this one, back to the first after the last one or a theme
+    /// that was loaded from a file
+    pub fn next(&self) -> Self {
+        let names: Vec<_> = Self::bundled_names().collect();
+        let index = names.iter().position(|&name| name == self.name);
+        let next = index.map_or(0, |index| (index + 1) % names.len());
+        Self::bundled(names[next]).unwrap()
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::bundled("classic").unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FruitEffect;
+
+    #[test]
+    fn bundled_themes_parse() {
+        for name in Theme::bundled_names() {
+            let theme = Theme::bundled(name).unwrap();
+            assert_eq!(theme.name, name);
+            assert_eq!(theme.next(), Theme::find(name).unwrap().next());
+        }
+        assert_eq!(Theme::default().next().next().next(), Theme::default());
+        assert!(Theme::parse("name = \"empty\"").is_err());
+    }
+
+    #[test]
+    fn themes_replace_fruit_colors_by_name() {
+        let table = FruitKind::default_table();
+        let theme = Theme::bundled("colorblind").unwrap();
+        assert_ne!(theme.fruit(&table[0]), table[0].color);
+        let classic = Theme::default();
+        for kind in &table {
+            assert_eq!(classic.fruit(kind), kind.color);
+        }
+    }
+
+    #[test]
+    fn poison_looks_like_nothing_else() {
+        let table = FruitKind::default_table();
+        let poison = table
+            .iter()
+            .find(|kind| kind.effect == FruitEffect::Poison)
+            .unwrap();
+        for name in Theme::bundled_names() {
+            let theme = Theme::bundled(name).unwrap();
+            let color = theme.fruit(poison);
+            let snakes = theme
+                .snakes
+                .iter()
+                .flat_map(|colors| [colors.head, colors.body]);
+            let power_ups = PowerUp::ALL
+                .into_iter()
+                .map(|power_up| theme.power_up(power_up));
+            let fruits = table
+                .iter()
+                .filter(|&kind| kind != poison)
+                .map(|kind| theme.fruit(kind));
+            let mut others = snakes.chain(power_ups).chain(fruits);
+            assert!(others.all(|other| other != color), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::{io, path::Path};
+
+use image::{imageops, imageops::FilterType, RgbaImage};
+
+use crate::{bundled, Direction};
+
+/// The tilesets that come with the game, by name
+const BUNDLED: [(&str, &[u8]); 1] = [("default", include_bytes!("../tilesets/default.png"))];
+
+/// The tiles in a row and a column of the sheet
+const GRID: u32 = 4;
+
+/// A piece of the picture drawn into a single cell
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Tile {
+    /// The head of a snake looking in the direction
+    Head(Direction),
+    /// The tail of a snake, the rest of it lies in the direction
+    Tail(Direction),
+    /// A segment joining the cells in two directions, a straight or a corner piece
+    Body(Direction, Direction),
+    Fruit,
+    Wall,
+}
+
+/// The sprites snakes, fruits and walls are drawn with, cut from a PNG sheet.
+///
+/// The sheet is a square grid of 4x4 square tiles. The first row holds the heads looking up,
+/// right, down and left, the second one the tails continuing up, right, down and left. The third
+/// row holds the corners joining up and right, right and down, down and left, and left and up,
+/// the last one the vertical and horizontal body pieces, the fruit and the wall. The tiles are
+/// drawn as light shapes on a transparent background, they are tinted with the color of the snake,
+/// fruit or wall they stand for.
+#[derive(Clone, Debug)]
+pub struct Tileset {
+    /// The tiles in reading order
+    tiles: Vec<RgbaImage>,
+}
+
+impl Tileset {
+    /// The names of the bundled tilesets
+    pub fn bundled_names() -> impl Iterator<Item = &'static str> {
+        bundled::names(&BUNDLED)
+    }
+
+    /// The bundled tileset with the given name
+    pub fn bundled(name: &str) -> Option<Self> {
+        bundled::get(&BUNDLED, name, |_, png| {
+            let image = image::load_from_memory(png).map_err(|e| e.to_string())?;
+            Self::from_image(image.to_rgba8())
+        })
+    }
+
+    /// Load a tileset from a PNG file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let image = image::open(path)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            .to_rgba8();
+        Self::from_image(image).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// The bundled tileset with the given name, otherwise the PNG file at that path
+    pub fn find(name: &str) -> Result<Self, String> {
+        bundled::find("tileset", name, Self::bundled, Self::load)
+    }
+
+    /// Cut a sheet laid out as described on [`Tileset`] into its tiles
+    pub fn from_image(sheet: RgbaImage) -> Result<Self, String> {
+        let (width, height) = sheet.dimensions();
+        if width != height || width == 0 || width % GRID != 0 {
+            return Err(format!(
+                "the sheet is {}x{} pixels, not a square grid of {}x{} tiles",
+                width, height, GRID, GRID
+            ));
+        }
+
+        let size = width / GRID;
+        let tiles = (0..GRID * GRID)
+            .map(|i| imageops::crop_imm(&sheet, i % GRID * size, i / GRID * size, size, size))
+            .map(|tile| tile.to_image())
+            .collect();
+        Ok(Self { tiles })
+    }
+
+    /// The width and height of a tile in pixels
+    pub fn tile_size(&self) -> u32 {
+        self.tiles[0].width()
+    }
+
+    /// The tileset with every tile scaled to the given size, keeping the pixels sharp when
+    /// enlarging
+    pub fn scaled(&self, size: u32) -> Self {
+        let filter = if size >= self.tile_size() {
+            FilterType::Nearest
+        } else {
+            FilterType::Triangle
+        };
+        let tiles = self
+            .tiles
+            .iter()
+            .map(|tile| imageops::resize(tile, size, size, filter))
+            .collect();
+        Self { tiles }
+    }
+
+    /// The picture of a tile, `None` for a body piece joining a cell with itself
+    pub fn tile(&self, tile: Tile) -> Option<&RgbaImage> {
+        let index = match tile {
+            Tile::Head(dir) => column(dir),
+            Tile::Tail(dir) => GRID + column(dir),
+            Tile::Body(a, b) if a == b.opposite() => match a {
+                Direction::Up | Direction::Down => 3 * GRID,
+                Direction::Left | Direction::Right => 3 * GRID + 1,
+            },
+            // corners are stored by the first of their directions in clockwise order
+            Tile::Body(a, b) if (column(a) + 1) % GRID == column(b) => 2 * GRID + column(a),
+            Tile::Body(a, b) if (column(b) + 1) % GRID == column(a) => 2 * GRID + column(b),
+            Tile::Body(..) => return None,
+            Tile::Fruit => 3 * GRID + 2,
+            Tile::Wall => 3 * GRID + 3,
+        };
+        Some(&self.tiles[index as usize])
+    }
+}
+
+/// The column of the tile facing in a direction, clockwise from up
+fn column(dir: Direction) -> u32 {
+    match dir {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_tilesets_have_every_tile() {
+        for name in Tileset::bundled_names() {
+            let tileset = Tileset::bundled(name).unwrap().scaled(40);
+            assert_eq!(tileset.tile_size(), 40);
+            for a in Direction::ALL {
+                assert!(tileset.tile(Tile::Head(a)).is_some());
+                assert!(tileset.tile(Tile::Tail(a)).is_some());
+                for b in Direction::ALL {
+                    assert_eq!(tileset.tile(Tile::Body(a, b)).is_some(), a != b);
+                    assert_eq!(
+                        tileset.tile(Tile::Body(a, b)),
+                        tileset.tile(Tile::Body(b, a))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sheets_are_square_grids() {
+        assert!(Tileset::from_image(RgbaImage::new(64, 48)).is_err());
+        assert!(Tileset::from_image(RgbaImage::new(30, 30)).is_err());
+        assert!(Tileset::from_image(RgbaImage::new(0, 0)).is_err());
+        assert_eq!(
+            Tileset::from_image(RgbaImage::new(32, 32))
+                .unwrap()
+                .tile_size(),
+            8
+        );
+    }
+}
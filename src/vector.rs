@@ -0,0 +1,100 @@
+use std::ops::{Add, AddAssign};
+
+/// A 2d point or direction
+#[derive(Clone, Copy, Debug, Hash, Default, PartialEq, Eq)]
+pub struct Vector2d {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Vector2d {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Wrap the point into the rectangle from the origin to `bounds`, like on a torus
+    pub fn rem_euclid(self, bounds: Vector2d) -> Self {
+        Self {
+            x: self.x.rem_euclid(bounds.x),
+            y: self.y.rem_euclid(bounds.y),
+        }
+    }
+
+    /// Whether the point lies in the rectangle from the origin to `bounds`
+    pub fn is_within(self, bounds: Vector2d) -> bool {
+        (0..bounds.x).contains(&self.x) && (0..bounds.y).contains(&self.y)
+    }
+}
+
+impl Add for Vector2d {
+    type Output = Self;
+    fn add(self, other: Self) -> Self::Output {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl AddAssign for Vector2d {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// A movement command for the snake
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// The unit vector pointing in this direction
+    pub fn to_vector(self) -> Vector2d {
+        match self {
+            Direction::Up => Vector2d::new(0, -1),
+            Direction::Down => Vector2d::new(0, 1),
+            Direction::Left => Vector2d::new(-1, 0),
+            Direction::Right => Vector2d::new(1, 0),
+        }
+    }
+
+    /// The direction written as one of `UDLR`, as used in replays and scripts
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'U' => Some(Direction::Up),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
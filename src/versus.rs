@@ -0,0 +1,102 @@
+use crate::World;
+
+/// How a round of a versus match ended
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RoundResult {
+    /// The player with this index won
+    Won(usize),
+    Draw,
+}
+
+impl RoundResult {
+    /// The result of a finished round: the last player alive wins, otherwise the best score does
+    pub fn of(world: &World) -> Self {
+        if let Some(survivor) = world.survivor() {
+            return RoundResult::Won(survivor);
+        }
+
+        let players = &world.snakes()[..world.players()];
+        let best = players.iter().map(|snake| snake.score()).max();
+        let mut best_players = (0..players.len()).filter(|&i| Some(players[i].score()) == best);
+        match (best_players.next(), best_players.next()) {
+            (Some(player), None) => RoundResult::Won(player),
+            _ => RoundResult::Draw,
+        }
+    }
+}
+
+/// The rounds won by each player of a versus match
+#[derive(Clone, Debug)]
+pub struct Scoreboard {
+    wins: Vec<u32>,
+    rounds: u32,
+    last_result: Option<RoundResult>,
+}
+
+impl Scoreboard {
+    /// The number of rounds a player has to win to win the match
+    pub const ROUNDS_TO_WIN: u32 = 3;
+
+    pub fn new(players: usize) -> Self {
+        Self {
+            wins: vec![0; players],
+            rounds: 0,
+            last_result: None,
+        }
+    }
+
+    pub fn record(&mut self, result: RoundResult) {
+        self.rounds += 1;
+        self.last_result = Some(result);
+        if let RoundResult::Won(player) = result {
+            self.wins[player] += 1;
+        }
+    }
+
+    /// The number of rounds won, per player
+    pub fn wins(&self) -> &[u32] {
+        &self.wins
+    }
+
+    /// The number of rounds finished
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    pub fn last_result(&self) -> Option<RoundResult> {
+        self.last_result
+    }
+
+    /// The player who won enough rounds to win the match
+    pub fn match_winner(&self) -> Option<usize> {
+        self.wins
+            .iter()
+            .position(|&wins| wins >= Self::ROUNDS_TO_WIN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_are_won_by_the_first_to_win_enough_rounds() {
+        let mut scoreboard = Scoreboard::new(2);
+        for result in [
+            RoundResult::Won(1),
+            RoundResult::Won(0),
+            RoundResult::Draw,
+            RoundResult::Won(0),
+            RoundResult::Won(1),
+        ] {
+            scoreboard.record(result);
+            assert_eq!(scoreboard.match_winner(), None);
+        }
+        assert_eq!(scoreboard.wins(), [2, 2]);
+
+        scoreboard.record(RoundResult::Won(1));
+        assert_eq!(scoreboard.match_winner(), Some(1));
+        assert_eq!(scoreboard.rounds(), 6);
+        assert_eq!(scoreboard.last_result(), Some(RoundResult::Won(1)));
+    }
+}
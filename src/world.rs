@@ -0,0 +1,956 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grid::Grid, Direction, Fruit, FruitEffect, FruitKind, GameConfig, Pickup, PowerUp, Rng, Snake,
+    Vector2d,
+};
+
+/// The points awarded for eating a fruit
+const FRUIT_POINTS: u32 = 10;
+/// The most fruits on the field at once, rare ones do not appear beyond it
+const MAX_FRUITS: usize = 4;
+/// How often a fruit is placed on a random free cell before giving up because it was taken by
+/// another fruit every time
+const FRUIT_ATTEMPTS: usize = 8;
+/// One in this many regular fruits is joined by a power-up
+const POWER_UP_RARITY: u32 = 6;
+/// The ticks a power-up stays on the field before it disappears
+const PICKUP_LIFETIME: u64 = 60;
+/// How many steps away from the head a magnet reaches fruits
+const MAGNET_RANGE: i32 = 5;
+
+/// What happened during a single [`World::update`]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// No snake has been given a direction yet, nothing moved
+    Idle,
+    /// The snakes moved one cell
+    Moved,
+    /// The snakes moved and one of them ate a fruit
+    Ate,
+    /// The snake died, or all but one of several snakes did, the world does not change anymore
+    Died,
+    /// The snakes filled the whole field, the world does not change anymore
+    Won,
+}
+
+impl StepOutcome {
+    /// Whether the game ended with this tick
+    pub fn is_over(self) -> bool {
+        matches!(self, StepOutcome::Died | StepOutcome::Won)
+    }
+}
+
+/// Why the snake died
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum DeathCause {
+    /// The snake left the field or ran into a wall of the level
+    Wall,
+    /// The snake ran into its own body
+    Body,
+    /// The snake ran into the body of another snake
+    Snake,
+    /// The snake ran head first into another snake's head
+    HeadOn,
+    /// The snake ate a poisonous fruit
+    Poison,
+}
+
+/// What happens when the snake reaches the edge of the field
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WallMode {
+    /// The edge is a wall, running into it kills the snake
+    #[default]
+    Solid,
+    /// The field is a torus, the snake reappears on the opposite side
+    Wrap,
+}
+
+impl WallMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            WallMode::Solid => "solid",
+            WallMode::Wrap => "wrap",
+        }
+    }
+
+    /// The mode with the given [`name`](Self::name)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "solid" => Some(WallMode::Solid),
+            "wrap" => Some(WallMode::Wrap),
+            _ => None,
+        }
+    }
+
+    /// The other mode
+    pub fn toggled(self) -> Self {
+        match self {
+            WallMode::Solid => WallMode::Wrap,
+            WallMode::Wrap => WallMode::Solid,
+        }
+    }
+}
+
+/// The snake simulation without any windowing or rendering attached
+#[derive(Clone, Debug)]
+pub struct World {
+    /// The snakes of the players, followed by those of computer controlled opponents
+    snakes: Vec<Snake>,
+    players: usize,
+    /// The cells taken by snakes and walls
+    grid: Grid,
+    /// The regular fruit, unless the snakes fill the whole field, and any rarer ones
+    fruits: Vec<Fruit>,
+    /// The power-ups waiting to be picked up
+    pickups: Vec<Pickup>,
+    /// The number of fruits eaten by all snakes, which the tick rate rises with
+    fruits_eaten: u32,
+    /// The number of ticks in which the snakes moved
+    ticks: u64,
+    /// The change of the tick rate from the last speed fruit, and the tick it ends at
+    speed_change: Option<(i32, u64)>,
+    config: GameConfig,
+    rng: Rng,
+}
+
+impl World {
+    /// A world with one snake per player and opponent, placed on the starts of the level or spread
+    /// out evenly over the middle row
+    pub fn new(config: &GameConfig, rng: Rng) -> Self {
+        let size = config.field_size();
+        let count = (config.players + config.opponents) as i32;
+        let mut me = Self {
+            snakes: Vec::with_capacity(count as usize),
+            players: config.players as usize,
+            grid: Grid::new(size),
+            fruits: Vec::new(),
+            pickups: Vec::new(),
+            fruits_eaten: 0,
+            ticks: 0,
+            speed_change: None,
+            config: config.clone(),
+            rng,
+        };
+
+        let starts = match &config.level {
+            Some(level) => {
+                for wall in level.walls() {
+                    me.grid.occupy(wall);
+                }
+                level.starts()
+            }
+            None => &[],
+        };
+        for i in 0..count {
+            let head = match starts.get(i as usize) {
+                Some(&start) => start,
+                None => {
+                    me.free_cell_from(Vector2d::new((i + 1) * size.x / (count + 1), size.y / 2))
+                }
+            };
+            me.snakes.push(Snake::new(head));
+            me.grid.occupy(head);
+        }
+        me.spawn_fruit(0);
+        me
+    }
+
+    /// The first free cell at or after `pos` in reading order, wrapping around the field
+    fn free_cell_from(&self, pos: Vector2d) -> Vector2d {
+        let size = self.config.field_size();
+        let cells = size.x * size.y;
+        (0..cells)
+            .map(|i| (pos.y * size.x + pos.x + i) % cells)
+            .map(|i| Vector2d::new(i % size.x, i / size.x))
+            .find(|&cell| !self.grid.is_occupied(cell))
+            .unwrap_or(pos)
+    }
+
+    /// Queue a turn of a snake for one of the next ticks, see [`Snake`] for which turns are kept
+    pub fn input(&mut self, snake: usize, dir: Direction) {
+        if let Some(snake) = self.snakes.get_mut(snake) {
+            snake.input(dir);
+        }
+    }
+
+    /// Advance the simulation by one tick, all snakes move at the same time
+    pub fn update(&mut self) -> StepOutcome {
+        if self.has_won() {
+            return StepOutcome::Won;
+        }
+        if self.is_over() {
+            return StepOutcome::Died;
+        }
+
+        // where every snake is heading, `None` for dead ones and those waiting for their first turn
+        let size = self.config.field_size();
+        let walls = self.config.walls;
+        let heads: Vec<_> = self
+            .snakes
+            .iter_mut()
+            .map(|snake| {
+                if snake.is_dead() {
+                    return None;
+                }
+
+                let head = snake.head() + snake.turn()?.to_vector();
+                Some(match walls {
+                    WallMode::Solid => head,
+                    WallMode::Wrap => head.rem_euclid(size),
+                })
+            })
+            .collect();
+        if heads.iter().all(Option::is_none) {
+            return StepOutcome::Idle;
+        }
+        self.ticks += 1;
+        self.expire_fruits();
+
+        // the tails move on unless their snake grows, so heads may take their cells
+        let mut tails = vec![None; self.snakes.len()];
+        let mut growth = vec![0; self.snakes.len()];
+        for (i, head) in heads.iter().enumerate() {
+            let head = match *head {
+                Some(head) => head,
+                None => continue,
+            };
+            if let Some(FruitEffect::Grow(cells)) =
+                self.fruit_at(head).map(|fruit| self.kind(fruit).effect)
+            {
+                growth[i] = cells;
+                self.snakes[i].grow(cells);
+            }
+            tails[i] = self.snakes[i].advance(head);
+            if let Some(tail) = tails[i] {
+                // a ghost may still cover the cell with another segment
+                if !self.snakes[i].body().any(|&cell| cell == tail) {
+                    self.grid.release(tail);
+                }
+            }
+        }
+
+        let deaths: Vec<_> = (0..self.snakes.len())
+            .map(|i| self.collision(i, &heads))
+            .collect();
+        let mut ate = false;
+        for (i, head) in heads.iter().enumerate() {
+            let head = match *head {
+                Some(head) => head,
+                None => continue,
+            };
+
+            match deaths[i] {
+                Some(_) if self.snakes[i].use_shield() => {
+                    let tail = tails[i];
+                    let keep_tail = tail.is_some_and(|tail| !heads.contains(&Some(tail)));
+                    self.snakes[i].retreat(tail, keep_tail);
+                    // the fruit it did not eat
+                    self.snakes[i].cancel_growth(growth[i]);
+                    self.snakes[i].tick_power_ups();
+                    if let Some(tail) = tail.filter(|_| keep_tail) {
+                        self.grid.occupy(tail);
+                    }
+                }
+                Some(cause) => {
+                    // crashed heads block their cell as well
+                    if head.is_within(size) {
+                        self.grid.occupy(head);
+                    }
+                    self.snakes[i].kill(cause);
+                }
+                None => {
+                    self.grid.occupy(head);
+                    // power-ups picked up now last their full duration from the next tick on
+                    self.snakes[i].tick_power_ups();
+                    if let Some(&fruit) = self.fruit_at(head) {
+                        ate |= self.eat(i, fruit);
+                    }
+                    if let Some(&pickup) = self.pickup_at(head) {
+                        self.snakes[i].pick_up(pickup.power_up);
+                    }
+                }
+            }
+        }
+
+        // crashed snakes take the fruits they reached with them
+        let regular_eaten = self
+            .fruit()
+            .is_some_and(|fruit| heads.contains(&Some(fruit)));
+        self.fruits
+            .retain(|fruit| !heads.contains(&Some(fruit.pos)));
+        self.pickups
+            .retain(|pickup| !heads.contains(&Some(pickup.pos)));
+        if regular_eaten {
+            self.create_fruits();
+        }
+        self.pull_fruits();
+
+        if self.has_won() {
+            StepOutcome::Won
+        } else if self.is_over() {
+            StepOutcome::Died
+        } else if ate {
+            StepOutcome::Ate
+        } else {
+            StepOutcome::Moved
+        }
+    }
+
+    /// Apply the effect of a fruit to the snake with the given index, returns whether it survives
+    fn eat(&mut self, index: usize, fruit: Fruit) -> bool {
+        let effect = self.kind(&fruit).effect;
+        if effect == FruitEffect::Poison {
+            self.snakes[index].kill(DeathCause::Poison);
+            return false;
+        }
+
+        let snake = &mut self.snakes[index];
+        let points = match effect {
+            FruitEffect::Bonus(points) => FRUIT_POINTS + points,
+            _ => FRUIT_POINTS,
+        };
+        snake.add_score(points * snake.score_multiplier());
+        self.fruits_eaten += 1;
+        match effect {
+            FruitEffect::Shrink(cells) => {
+                for cell in self.snakes[index].shrink(cells) {
+                    // a ghost may still cover the cell with another segment
+                    if !self.snakes[index].cells().contains(&cell) {
+                        self.grid.release(cell);
+                    }
+                }
+            }
+            FruitEffect::Speed { change, ticks } => {
+                self.speed_change = Some((change, self.ticks + ticks as u64));
+            }
+            // growing starts right away, before the tails move
+            FruitEffect::Grow(_) | FruitEffect::Bonus(_) | FruitEffect::Poison => (),
+        }
+        true
+    }
+
+    /// What the snake with the given index dies of after moving its head to `heads[index]`
+    fn collision(&self, index: usize, heads: &[Option<Vector2d>]) -> Option<DeathCause> {
+        let head = heads[index]?;
+        if !head.is_within(self.config.field_size()) {
+            return Some(DeathCause::Wall);
+        }
+
+        // meeting in a cell or passing through each other
+        let prev = self.snakes[index].prev_head();
+        let head_on = heads.iter().enumerate().any(|(other, &other_head)| {
+            other != index
+                && (other_head == Some(head)
+                    || (other_head == Some(prev) && self.snakes[other].prev_head() == head))
+        });
+        if head_on {
+            return Some(DeathCause::HeadOn);
+        }
+
+        if self.is_wall(head) {
+            return Some(DeathCause::Wall);
+        }
+        if self.grid.is_occupied(head) {
+            let snake = &self.snakes[index];
+            let own = snake.body().any(|&cell| cell == head);
+            if own && snake.power_up(PowerUp::Ghost).is_some() {
+                return None;
+            }
+            return Some(if own {
+                DeathCause::Body
+            } else {
+                DeathCause::Snake
+            });
+        }
+
+        None
+    }
+
+    /// All snakes, those of the players first
+    pub fn snakes(&self) -> &[Snake] {
+        &self.snakes
+    }
+
+    /// The snake with the given index, the players' snakes come first
+    pub fn snake(&self, index: usize) -> &Snake {
+        &self.snakes[index]
+    }
+
+    /// The number of snakes steered by players, the rest belong to opponents
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Where the regular fruit is, `None` once the snakes fill the whole field or while fruits and
+    /// power-ups take all cells left
+    pub fn fruit(&self) -> Option<Vector2d> {
+        self.fruits
+            .iter()
+            .find(|fruit| fruit.kind == 0)
+            .map(|fruit| fruit.pos)
+    }
+
+    /// All fruits on the field
+    pub fn fruits(&self) -> &[Fruit] {
+        &self.fruits
+    }
+
+    /// The fruit lying on a cell
+    pub fn fruit_at(&self, pos: Vector2d) -> Option<&Fruit> {
+        self.fruits.iter().find(|fruit| fruit.pos == pos)
+    }
+
+    /// The entry of the fruit table a fruit belongs to
+    pub fn kind(&self, fruit: &Fruit) -> &FruitKind {
+        &self.config.fruits[fruit.kind]
+    }
+
+    /// All power-ups waiting on the field
+    pub fn pickups(&self) -> &[Pickup] {
+        &self.pickups
+    }
+
+    /// The power-up lying on a cell
+    pub fn pickup_at(&self, pos: Vector2d) -> Option<&Pickup> {
+        self.pickups.iter().find(|pickup| pickup.pos == pos)
+    }
+
+    pub fn fruits_eaten(&self) -> u32 {
+        self.fruits_eaten
+    }
+
+    /// The ticks per second the world should currently be updated with
+    pub fn tick_rate(&self) -> u32 {
+        let mut rate = self.config.speed_curve().tick_rate(self.fruits_eaten);
+        if let Some((change, until)) = self.speed_change {
+            if self.ticks < until {
+                rate = (rate as i32 + change).max(1) as u32;
+            }
+        }
+        // any player's slow motion slows down everyone
+        let slow_motion = self.snakes[..self.players]
+            .iter()
+            .any(|snake| !snake.is_dead() && snake.power_up(PowerUp::SlowMotion).is_some());
+        if slow_motion {
+            rate = (rate / 2).max(1);
+        }
+        rate
+    }
+
+    /// The ticks left until the tick rate returns to normal after eating a speed fruit
+    pub fn speed_change_left(&self) -> Option<u64> {
+        let (_, until) = self.speed_change?;
+        until.checked_sub(self.ticks).filter(|&left| left > 0)
+    }
+
+    /// The number of ticks in which the snakes moved
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Whether the snakes fill the whole field, leaving no room for another fruit
+    pub fn has_won(&self) -> bool {
+        self.grid.free_count() == 0
+    }
+
+    /// Whether the game is over: the field is full, the only player died or, with several
+    /// players, at most one is left. Opponents do not count.
+    pub fn is_over(&self) -> bool {
+        let alive = self.snakes[..self.players]
+            .iter()
+            .filter(|snake| !snake.is_dead())
+            .count();
+        self.has_won() || alive < self.players.min(2)
+    }
+
+    /// The index of the last player alive in a game of several players
+    pub fn survivor(&self) -> Option<usize> {
+        if self.players < 2 {
+            return None;
+        }
+
+        let mut alive = (0..self.players).filter(|&i| !self.snakes[i].is_dead());
+        match (alive.next(), alive.next()) {
+            (Some(survivor), None) => Some(survivor),
+            _ => None,
+        }
+    }
+
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    pub fn wall_mode(&self) -> WallMode {
+        self.config.walls
+    }
+
+    /// Where a snake moving to `pos` ends up, the opposite side of a wrapping field for positions
+    /// beyond its edge
+    pub fn wrap(&self, pos: Vector2d) -> Vector2d {
+        match self.config.walls {
+            WallMode::Solid => pos,
+            WallMode::Wrap => pos.rem_euclid(self.config.field_size()),
+        }
+    }
+
+    /// Whether `pos` is one of the walls of the level
+    pub fn is_wall(&self, pos: Vector2d) -> bool {
+        matches!(&self.config.level, Some(level) if level.is_wall(pos))
+    }
+
+    /// Whether a snake head moving to `pos` would die, poisonous fruits included
+    pub fn is_blocked(&self, pos: Vector2d) -> bool {
+        let pos = self.wrap(pos);
+        let poison = self
+            .fruit_at(pos)
+            .is_some_and(|fruit| self.kind(fruit).effect == FruitEffect::Poison);
+        self.grid.is_occupied(pos) || poison
+    }
+
+    /// The number of cells taken by neither snakes nor walls
+    pub fn free_cells(&self) -> usize {
+        self.grid.free_count()
+    }
+
+    /// Place a new regular fruit, and each rarer kind by its chance next to it
+    fn create_fruits(&mut self) {
+        self.spawn_fruit(0);
+        for kind in 1..self.config.fruits.len() {
+            let rarity = self.config.fruits[kind].rarity;
+            if self.fruits.len() < MAX_FRUITS && rarity > 0 && self.rng.gen_below(rarity) == 0 {
+                self.spawn_fruit(kind);
+            }
+        }
+
+        if self.config.power_ups && self.rng.gen_below(POWER_UP_RARITY) == 0 {
+            let power_up = PowerUp::ALL[self.rng.gen_below(PowerUp::ALL.len() as u32) as usize];
+            if let Some(pos) = self.random_empty_cell() {
+                self.pickups.push(Pickup {
+                    pos,
+                    power_up,
+                    expires: self.ticks + PICKUP_LIFETIME,
+                });
+            }
+        }
+    }
+
+    /// Place a fruit of the given kind on a free cell without a fruit, chosen uniformly
+    fn spawn_fruit(&mut self, kind: usize) {
+        let expires = self.config.fruits[kind]
+            .lifetime
+            .map(|lifetime| self.ticks + lifetime as u64);
+        if let Some(pos) = self.random_empty_cell() {
+            self.fruits.push(Fruit { pos, kind, expires });
+        }
+    }
+
+    /// A free cell without a fruit or power-up, chosen uniformly, `None` if none was found
+    fn random_empty_cell(&mut self) -> Option<Vector2d> {
+        for _ in 0..FRUIT_ATTEMPTS {
+            let pos = self.grid.random_free(&mut self.rng)?;
+            if self.is_empty(pos) {
+                return Some(pos);
+            }
+        }
+        None
+    }
+
+    /// Whether a free cell holds neither a fruit nor a power-up
+    fn is_empty(&self, pos: Vector2d) -> bool {
+        self.fruit_at(pos).is_none() && self.pickup_at(pos).is_none()
+    }
+
+    /// Remove the fruits and power-ups whose lifetime is over, and place the regular fruit again
+    /// if it expired or found no room when it was eaten
+    fn expire_fruits(&mut self) {
+        let ticks = self.ticks;
+        self.fruits
+            .retain(|fruit| fruit.expires.is_none_or(|expires| expires > ticks));
+        self.pickups.retain(|pickup| pickup.expires > ticks);
+        if self.fruit().is_none() {
+            self.spawn_fruit(0);
+        }
+    }
+
+    /// Move the fruits within reach of a snake's magnet one cell towards its head, along the
+    /// longer distance first. Poisonous fruits stay where they are.
+    fn pull_fruits(&mut self) {
+        for snake in &self.snakes {
+            if snake.is_dead() || snake.power_up(PowerUp::Magnet).is_none() {
+                continue;
+            }
+
+            let head = snake.head();
+            for i in 0..self.fruits.len() {
+                let fruit = self.fruits[i];
+                if self.kind(&fruit).effect == FruitEffect::Poison {
+                    continue;
+                }
+                let (dx, dy) = (head.x - fruit.pos.x, head.y - fruit.pos.y);
+                if dx.abs() + dy.abs() > MAGNET_RANGE {
+                    continue;
+                }
+
+                let across = Vector2d::new(dx.signum(), 0);
+                let down = Vector2d::new(0, dy.signum());
+                let steps = if dx.abs() >= dy.abs() {
+                    [across, down]
+                } else {
+                    [down, across]
+                };
+                let pos = steps
+                    .into_iter()
+                    .filter(|&step| step != Vector2d::new(0, 0))
+                    .map(|step| fruit.pos + step)
+                    .find(|&pos| !self.grid.is_occupied(pos) && self.is_empty(pos));
+                if let Some(pos) = pos {
+                    self.fruits[i].pos = pos;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Level, RoundResult};
+
+    fn world(walls: WallMode) -> World {
+        let config = GameConfig {
+            field_width: 10,
+            field_height: 10,
+            walls,
+            ..GameConfig::default()
+        };
+        World::new(&config, Rng::new(1))
+    }
+
+    /// Two snakes on a 10x10 field, starting at (3, 5) and (6, 5)
+    fn versus() -> World {
+        let config = GameConfig {
+            field_width: 10,
+            field_height: 10,
+            players: 2,
+            ..GameConfig::default()
+        };
+        let mut world = World::new(&config, Rng::new(1));
+        place_fruit(&mut world, Vector2d::new(0, 9));
+        world
+    }
+
+    /// Make a regular fruit at `pos` the only one on the field
+    fn place_fruit(world: &mut World, pos: Vector2d) {
+        world.fruits = vec![Fruit {
+            pos,
+            kind: 0,
+            expires: None,
+        }];
+    }
+
+    /// Put the fruit right in front of the snake after turning into `dir`, then move onto it
+    fn eat(world: &mut World, dir: Direction) -> StepOutcome {
+        let fruit = (world.snake(0).head() + dir.to_vector()).rem_euclid(world.config.field_size());
+        place_fruit(world, fruit);
+        world.input(0, dir);
+        world.update()
+    }
+
+    /// Move into `dir` with the fruit out of the way
+    fn step(world: &mut World, dir: Direction) -> StepOutcome {
+        place_fruit(world, Vector2d::new(0, 9));
+        world.input(0, dir);
+        world.update()
+    }
+
+    fn cells(cells: &[(i32, i32)]) -> Vec<Vector2d> {
+        cells.iter().map(|&(x, y)| Vector2d::new(x, y)).collect()
+    }
+
+    fn snake(world: &World) -> Vec<Vector2d> {
+        world.snake(0).cells().iter().copied().collect()
+    }
+
+    #[test]
+    fn growth_keeps_the_old_tail_cell() {
+        let mut world = world(WallMode::Solid);
+        assert_eq!(eat(&mut world, Direction::Right), StepOutcome::Ate);
+        assert_eq!(snake(&world), cells(&[(6, 5), (5, 5)]));
+    }
+
+    #[test]
+    fn growth_after_corners_follows_the_path() {
+        let mut world = world(WallMode::Solid);
+        eat(&mut world, Direction::Right);
+        eat(&mut world, Direction::Down);
+        eat(&mut world, Direction::Left);
+        assert_eq!(snake(&world), cells(&[(5, 6), (6, 6), (6, 5), (5, 5)]));
+
+        // the tail leaves the cells in the order the head entered them
+        eat(&mut world, Direction::Down);
+        step(&mut world, Direction::Right);
+        assert_eq!(
+            snake(&world),
+            cells(&[(6, 7), (5, 7), (5, 6), (6, 6), (6, 5)])
+        );
+        step(&mut world, Direction::Up);
+        assert_eq!(
+            snake(&world),
+            cells(&[(6, 6), (6, 7), (5, 7), (5, 6), (6, 6)])
+        );
+        assert_eq!(world.snake(0).death_cause(), Some(DeathCause::Body));
+    }
+
+    #[test]
+    fn head_may_take_the_cell_the_tail_leaves() {
+        let mut world = world(WallMode::Solid);
+        eat(&mut world, Direction::Right);
+        eat(&mut world, Direction::Down);
+        eat(&mut world, Direction::Left);
+        assert_eq!(step(&mut world, Direction::Up), StepOutcome::Moved);
+        assert_eq!(snake(&world), cells(&[(5, 5), (5, 6), (6, 6), (6, 5)]));
+    }
+
+    #[test]
+    fn growth_across_a_wrapping_edge() {
+        let mut world = world(WallMode::Wrap);
+        for _ in 0..4 {
+            step(&mut world, Direction::Right);
+        }
+        eat(&mut world, Direction::Right);
+        eat(&mut world, Direction::Up);
+        assert_eq!(snake(&world), cells(&[(0, 4), (0, 5), (9, 5)]));
+        step(&mut world, Direction::Left);
+        assert_eq!(snake(&world), cells(&[(9, 4), (0, 4), (0, 5)]));
+    }
+
+    #[test]
+    fn free_cells_shrink_with_growth_only() {
+        let mut world = world(WallMode::Solid);
+        assert_eq!(world.free_cells(), 99);
+        step(&mut world, Direction::Right);
+        assert_eq!(world.free_cells(), 99);
+        eat(&mut world, Direction::Down);
+        assert_eq!(world.free_cells(), 98);
+    }
+
+    #[test]
+    fn heads_meeting_in_a_cell_kill_both() {
+        let mut world = versus();
+        world.input(0, Direction::Right);
+        world.input(1, Direction::Left);
+        assert_eq!(world.update(), StepOutcome::Moved);
+        // (4, 5) and (5, 5) now, passing through each other counts as head on as well
+        assert_eq!(world.update(), StepOutcome::Died);
+        for snake in world.snakes() {
+            assert_eq!(snake.death_cause(), Some(DeathCause::HeadOn));
+        }
+        assert_eq!(world.survivor(), None);
+        assert_eq!(RoundResult::of(&world), RoundResult::Draw);
+    }
+
+    #[test]
+    fn running_into_another_snake_leaves_a_survivor() {
+        let mut world = versus();
+        place_fruit(&mut world, Vector2d::new(4, 5));
+        world.input(0, Direction::Right);
+        assert_eq!(world.update(), StepOutcome::Ate);
+        place_fruit(&mut world, Vector2d::new(0, 9));
+        assert_eq!(world.update(), StepOutcome::Moved);
+        // the second snake is still waiting for its first turn at (6, 5)
+        assert_eq!(world.update(), StepOutcome::Died);
+        assert_eq!(world.snake(0).death_cause(), Some(DeathCause::Snake));
+        assert_eq!(world.survivor(), Some(1));
+        assert_eq!(RoundResult::of(&world), RoundResult::Won(1));
+    }
+
+    #[test]
+    fn without_a_survivor_the_best_score_wins_the_round() {
+        let mut world = versus();
+        place_fruit(&mut world, Vector2d::new(4, 5));
+        world.input(0, Direction::Right);
+        world.input(1, Direction::Left);
+        assert_eq!(world.update(), StepOutcome::Ate);
+        place_fruit(&mut world, Vector2d::new(0, 9));
+        assert_eq!(world.update(), StepOutcome::Died);
+        assert_eq!(world.survivor(), None);
+        assert!(world.snake(0).score() > world.snake(1).score());
+        assert_eq!(RoundResult::of(&world), RoundResult::Won(0));
+    }
+
+    #[test]
+    fn a_crashed_opponent_does_not_end_the_game() {
+        let config = GameConfig {
+            field_width: 10,
+            field_height: 10,
+            opponents: 1,
+            ..GameConfig::default()
+        };
+        let mut world = World::new(&config, Rng::new(1));
+        place_fruit(&mut world, Vector2d::new(0, 9));
+        // the opponent starts at (6, 5) and hits the wall on the sixth tick
+        world.input(1, Direction::Up);
+        for _ in 0..6 {
+            assert_eq!(world.update(), StepOutcome::Moved);
+        }
+        assert_eq!(world.snake(1).death_cause(), Some(DeathCause::Wall));
+        assert!(!world.is_over());
+        assert_eq!(world.survivor(), None);
+    }
+
+    #[test]
+    fn level_walls_kill_and_stay_free_of_fruit() {
+        let level = Level::parse("S#..\n.#..\n.#..\n....\n", "test").unwrap();
+        let config = GameConfig {
+            level: Some(level),
+            ..GameConfig::default()
+        };
+        let mut world = World::new(&config, Rng::new(1));
+        assert_eq!(world.snake(0).head(), Vector2d::new(0, 0));
+        assert_eq!(world.free_cells(), 12);
+        assert!(!world.is_wall(world.fruit().unwrap()));
+
+        world.input(0, Direction::Right);
+        assert_eq!(world.update(), StepOutcome::Died);
+        assert_eq!(world.snake(0).death_cause(), Some(DeathCause::Wall));
+    }
+
+    /// Put a fruit of the named kind of the default table at `pos`, next to the regular fruit
+    fn place_kind(world: &mut World, pos: Vector2d, name: &str) {
+        place_fruit(world, Vector2d::new(0, 9));
+        let kind = world
+            .config
+            .fruits
+            .iter()
+            .position(|kind| kind.name == name)
+            .unwrap();
+        let expires = world.config.fruits[kind]
+            .lifetime
+            .map(|lifetime| world.ticks + lifetime as u64);
+        world.fruits.push(Fruit { pos, kind, expires });
+    }
+
+    #[test]
+    fn fruits_grow_and_shrink_the_snake() {
+        let mut world = world(WallMode::Solid);
+        place_kind(&mut world, Vector2d::new(6, 5), "melon");
+        world.input(0, Direction::Right);
+        assert_eq!(world.update(), StepOutcome::Ate);
+        assert_eq!(world.snake(0).length(), 2);
+        world.update();
+        world.update();
+        assert_eq!(world.snake(0).length(), 4);
+        assert!(!world.snake(0).is_growing());
+        assert_eq!(world.free_cells(), 96);
+
+        // the head stays, the cells of the tail are free again
+        place_kind(&mut world, Vector2d::new(9, 5), "ice");
+        world.update();
+        assert_eq!(snake(&world), cells(&[(9, 5)]));
+        assert_eq!(world.free_cells(), 99);
+    }
+
+    #[test]
+    fn poison_kills_and_bonus_fruits_expire() {
+        let mut world = world(WallMode::Wrap);
+        step(&mut world, Direction::Right);
+        place_kind(&mut world, Vector2d::new(0, 0), "golden");
+        for _ in 0..49 {
+            world.update();
+        }
+        assert!(world.fruit_at(Vector2d::new(0, 0)).is_some());
+        world.update();
+        assert!(world.fruit_at(Vector2d::new(0, 0)).is_none());
+        assert!(world.fruit().is_some());
+
+        let below = world.snake(0).head() + Vector2d::new(0, 1);
+        place_kind(&mut world, below, "poison");
+        assert!(world.is_blocked(below));
+        world.input(0, Direction::Down);
+        assert_eq!(world.update(), StepOutcome::Died);
+        assert_eq!(world.snake(0).death_cause(), Some(DeathCause::Poison));
+    }
+
+    #[test]
+    fn shields_absorb_one_collision_each() {
+        let mut world = world(WallMode::Solid);
+        world.snakes[0].pick_up(PowerUp::Shield);
+        for _ in 0..4 {
+            step(&mut world, Direction::Right);
+        }
+        assert_eq!(world.snake(0).head(), Vector2d::new(9, 5));
+        assert_eq!(world.update(), StepOutcome::Moved);
+        assert_eq!(world.snake(0).head(), Vector2d::new(9, 5));
+        assert_eq!(world.free_cells(), 99);
+        assert_eq!(world.update(), StepOutcome::Died);
+    }
+
+    #[test]
+    fn ghosts_pass_through_their_own_body() {
+        let mut world = world(WallMode::Solid);
+        place_kind(&mut world, Vector2d::new(6, 5), "melon");
+        world.input(0, Direction::Right);
+        world.update();
+        world.update();
+        world.update();
+        world.snakes[0].pick_up(PowerUp::Ghost);
+        step(&mut world, Direction::Down);
+        step(&mut world, Direction::Left);
+        assert_eq!(step(&mut world, Direction::Up), StepOutcome::Moved);
+        assert_eq!(snake(&world), cells(&[(7, 5), (7, 6), (8, 6), (8, 5)]));
+        // the cell the ghost crossed stays taken while its head is on it
+        assert_eq!(world.free_cells(), 96);
+        assert!(world.is_blocked(Vector2d::new(7, 5)));
+    }
+
+    #[test]
+    fn ghosts_shrinking_keep_the_cells_they_still_cover() {
+        let mut world = world(WallMode::Solid);
+        place_kind(&mut world, Vector2d::new(6, 5), "melon");
+        world.input(0, Direction::Right);
+        world.update();
+        world.snakes[0].grow(1);
+        world.snakes[0].pick_up(PowerUp::Ghost);
+        step(&mut world, Direction::Right);
+        step(&mut world, Direction::Right);
+        step(&mut world, Direction::Down);
+        step(&mut world, Direction::Left);
+
+        // the head enters the cell of the tail, which stays behind as the snake is full grown
+        place_kind(&mut world, Vector2d::new(7, 5), "ice");
+        world.input(0, Direction::Up);
+        assert_eq!(world.update(), StepOutcome::Ate);
+        assert_eq!(snake(&world), cells(&[(7, 5), (7, 6)]));
+        assert_eq!(world.free_cells(), 98);
+        assert!(world.is_blocked(Vector2d::new(7, 5)));
+    }
+
+    #[test]
+    fn shielded_snakes_do_not_grow_from_fruit_they_bounce_off() {
+        let mut world = versus();
+        world.snakes[0].pick_up(PowerUp::Shield);
+        world.input(0, Direction::Right);
+        world.update();
+        place_kind(&mut world, Vector2d::new(5, 5), "melon");
+        world.input(0, Direction::Right);
+        world.input(1, Direction::Left);
+        world.update();
+        assert_eq!(snake(&world), cells(&[(4, 5)]));
+        assert!(!world.snake(0).is_growing());
+        assert_eq!(world.snake(1).death_cause(), Some(DeathCause::HeadOn));
+    }
+
+    #[test]
+    fn magnets_pull_fruit_towards_the_head() {
+        let mut world = world(WallMode::Solid);
+        world.snakes[0].pick_up(PowerUp::Magnet);
+        place_fruit(&mut world, Vector2d::new(6, 8));
+        world.input(0, Direction::Left);
+        world.update();
+        assert_eq!(world.fruit(), Some(Vector2d::new(6, 7)));
+        world.update();
+        assert_eq!(world.fruit(), Some(Vector2d::new(5, 7)));
+    }
+}